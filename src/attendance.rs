@@ -0,0 +1,248 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use rust_xlsxwriter::utility::cell_range;
+use rust_xlsxwriter::{
+  ConditionalFormatCell, ConditionalFormatCellRule, Format, FormatAlign, Formula, Workbook,
+};
+
+use crate::config::{Config, SCENE_MARK, SILENT_PLAY_MARK};
+use crate::i18n::{Language, Phrase};
+use crate::structures::{CallType, Person, PersonToSceneAndScheduleEntry, SceneSchedulerError};
+
+const OBSERVE_MARK: &str = "o";
+const FILE_NAME_SUFFIX: &str = "attendance";
+const DATE_FORMAT: &str = "%d.%m.%Y";
+const NAME_COLUMN_WIDTH: f64 = 24.0;
+const DATE_COLUMN_WIDTH: f64 = 4.0;
+
+/// What is entered for a person on a date. If someone is called more than
+/// once a day, the strongest call wins. Entries lasting several days are
+/// entered on each of them.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum Mark {
+  Observe,
+  SilentPlay,
+  Called,
+}
+
+impl Mark {
+  const ALL: [Mark; 3] = [Mark::Called, Mark::SilentPlay, Mark::Observe];
+
+  fn from_call_type(call_type: CallType) -> Self {
+    match call_type {
+      CallType::Scenes | CallType::Everyone => Mark::Called,
+      CallType::SilentPlay => Mark::SilentPlay,
+      CallType::Observe => Mark::Observe,
+    }
+  }
+
+  fn text(&self) -> &'static str {
+    match self {
+      Mark::Called => SCENE_MARK,
+      Mark::SilentPlay => SILENT_PLAY_MARK,
+      Mark::Observe => OBSERVE_MARK,
+    }
+  }
+
+  /// Fill color of the conditional format.
+  fn color(&self) -> &'static str {
+    match self {
+      Mark::Called => "C6EFCE",
+      Mark::SilentPlay => "FFEB9C",
+      Mark::Observe => "D9D9D9",
+    }
+  }
+}
+
+/// People as rows and the rehearsal dates as columns.
+#[derive(Debug)]
+struct Matrix<'a> {
+  dates: Vec<NaiveDate>,
+  rows: Vec<(&'a Person, Vec<Option<Mark>>)>,
+}
+
+fn attendance_matrix<'a>(
+  person_to_scene_and_schedule_entry: &'a PersonToSceneAndScheduleEntry,
+) -> Matrix<'a> {
+  let dates = person_to_scene_and_schedule_entry
+    .iter()
+    .flat_map(|(_, entries)| {
+      entries
+        .iter()
+        .flat_map(|(schedule_entry, _)| schedule_entry.dates())
+    })
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect::<Vec<_>>();
+  let columns = dates
+    .iter()
+    .enumerate()
+    .map(|(column, date)| (*date, column))
+    .collect::<HashMap<_, _>>();
+  let mut rows = person_to_scene_and_schedule_entry
+    .iter()
+    .map(|(person, entries)| {
+      let mut marks = vec![None; dates.len()];
+      for (schedule_entry, scene_entry) in entries {
+        let mark = Mark::from_call_type(schedule_entry.call_type_for(*scene_entry, person));
+        for date in schedule_entry.dates() {
+          let cell = &mut marks[columns[&date]];
+          *cell = (*cell).max(Some(mark));
+        }
+      }
+      (person, marks)
+    })
+    .collect::<Vec<_>>();
+  rows.sort_by_key(|(person, _)| *person);
+  Matrix { dates, rows }
+}
+
+/// Writes the attendance matrix as a new workbook next to the excel file and
+/// returns its path.
+pub fn write_attendance_matrix(
+  person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+  config: &Config,
+  language: Language,
+) -> Result<PathBuf, SceneSchedulerError> {
+  let matrix = attendance_matrix(person_to_scene_and_schedule_entry);
+  let mut workbook = Workbook::new();
+  let worksheet = workbook.add_worksheet();
+  worksheet.set_name(language.tr(Phrase::AttendanceSheet))?;
+  let bold = Format::new().set_bold();
+  let date_header = Format::new()
+    .set_bold()
+    .set_rotation(90)
+    .set_align(FormatAlign::Center);
+  let centered = Format::new().set_align(FormatAlign::Center);
+
+  let total_column = matrix.dates.len() as u16 + 1;
+  worksheet.write_string_with_format(0, 0, &config.production_name, &bold)?;
+  worksheet.set_column_width(0, NAME_COLUMN_WIDTH)?;
+  for (column, date) in (1..).zip(&matrix.dates) {
+    worksheet.write_string_with_format(
+      0,
+      column,
+      date.format(DATE_FORMAT).to_string(),
+      &date_header,
+    )?;
+    worksheet.set_column_width(column, DATE_COLUMN_WIDTH)?;
+  }
+  worksheet.write_string_with_format(0, total_column, language.tr(Phrase::TotalLabel), &bold)?;
+
+  for (row, (person, marks)) in (1..).zip(&matrix.rows) {
+    worksheet.write_string(row, 0, *person)?;
+    for (column, mark) in (1..).zip(marks) {
+      if let Some(mark) = mark {
+        worksheet.write_string_with_format(row, column, mark.text(), &centered)?;
+      }
+    }
+    let total = if matrix.dates.is_empty() {
+      "=0".to_owned()
+    } else {
+      format!("=COUNTA({})", cell_range(row, 1, row, total_column - 1))
+    };
+    // The result is stored as well for viewers which do not recalculate.
+    let count = marks.iter().flatten().count();
+    worksheet.write_formula_with_format(
+      row,
+      total_column,
+      Formula::new(total).set_result(count.to_string()),
+      &bold,
+    )?;
+  }
+
+  if !matrix.dates.is_empty() && !matrix.rows.is_empty() {
+    let last_row = matrix.rows.len() as u32;
+    for mark in Mark::ALL {
+      let conditional_format = ConditionalFormatCell::new()
+        .set_rule(ConditionalFormatCellRule::EqualTo(mark.text()))
+        .set_format(Format::new().set_background_color(mark.color()));
+      worksheet.add_conditional_format(1, 1, last_row, total_column - 1, &conditional_format)?;
+    }
+  }
+  worksheet.set_freeze_panes(1, 1)?;
+
+  let path = attendance_path(&config.excel_file_path);
+  workbook.save(&path)?;
+  Ok(path)
+}
+
+/// `Probenplan.xlsx` becomes `Probenplan_attendance.xlsx` in the same
+/// directory.
+fn attendance_path(excel_file_path: &str) -> PathBuf {
+  let excel_file_path = Path::new(excel_file_path);
+  let stem = excel_file_path
+    .file_stem()
+    .map(|stem| stem.to_string_lossy())
+    .unwrap_or_default();
+  excel_file_path.with_file_name(format!("{}_{}.xlsx", stem, FILE_NAME_SUFFIX))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::structures::{SceneEntry, Scenes, ScheduleEntry};
+  use chrono::NaiveTime;
+
+  fn schedule_entry(day: u32, hour: u32, scene: &str) -> ScheduleEntry {
+    ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 5, day).unwrap(),
+      (NaiveTime::from_hms_opt(hour, 0, 0).unwrap(), None),
+      Scenes::Normal(vec![scene.to_string()]),
+      None,
+      None,
+    )
+  }
+
+  #[test]
+  fn test_attendance_matrix() {
+    let morning = schedule_entry(1, 10, "1");
+    let afternoon = schedule_entry(1, 14, "2");
+    let next_day = schedule_entry(3, 10, "1");
+    let gretchen = SceneEntry::new(
+      "Gretchen".to_string(),
+      "Anna".to_string(),
+      vec!["1".to_string(), "2".to_string()],
+      vec![true, false],
+    );
+    let calls = [
+      (&morning, Some(&gretchen)),
+      (&afternoon, Some(&gretchen)),
+      (&next_day, Some(&gretchen)),
+      (&next_day, None),
+    ];
+    let person_to_scene_and_schedule_entry = vec![
+      ("Bert".to_string(), vec![&calls[3]]),
+      ("Anna".to_string(), vec![&calls[0], &calls[1], &calls[2]]),
+    ];
+    let matrix = attendance_matrix(&person_to_scene_and_schedule_entry);
+    assert_eq!(
+      matrix.dates,
+      vec![
+        NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2022, 5, 3).unwrap()
+      ]
+    );
+    assert_eq!(
+      matrix
+        .rows
+        .iter()
+        .map(|(person, marks)| (person.as_str(), marks.clone()))
+        .collect::<Vec<_>>(),
+      vec![
+        ("Anna", vec![Some(Mark::Called), Some(Mark::SilentPlay)]),
+        ("Bert", vec![None, Some(Mark::Called)]),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_attendance_path() {
+    assert_eq!(
+      attendance_path("plans/Probenplan.xlsx"),
+      Path::new("plans/Probenplan_attendance.xlsx")
+    );
+  }
+}
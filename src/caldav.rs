@@ -0,0 +1,392 @@
+use std::collections::{BTreeMap, HashSet};
+
+use base64::Engine;
+use ics::Event;
+use lazy_static::lazy_static;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::{Language, Phrase};
+use crate::ics::{create_person_events, single_event_calendar, Participants};
+use crate::structures::{PersonToSceneAndScheduleEntry, SceneSchedulerError};
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#;
+
+lazy_static! {
+  static ref HREF: Regex = Regex::new(r"<(?:[A-Za-z0-9]+:)?href[^>]*>([^<]*)</").unwrap();
+  /// Names of the resources written by us: the UID of the event.
+  static ref OWN_RESOURCE: Regex = Regex::new(r"^[0-9a-f]{32}\.ics$").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalDavConfig {
+  /// URL of the calendar collection of a person, e.g.
+  /// `http://localhost:5232/theater/{production}-{person}/`. The collection is
+  /// created if it does not exist yet.
+  pub url: String,
+  #[serde(default)]
+  pub username: Option<String>,
+  #[serde(default)]
+  pub password: Option<String>,
+}
+
+/// Uploads the events of every person into their calendar collection. Events
+/// are stored under their UID, so existing ones are updated, and events which
+/// are no longer in the schedule are deleted. A collection shared by several
+/// people is synced once with the events of all of them, so that they do not
+/// delete each other's events.
+pub fn push_to_caldav(
+  person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+  participants: &Participants,
+  caldav: &CalDavConfig,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<(), SceneSchedulerError> {
+  let client = CalDavClient::new(caldav);
+  let mut collections = BTreeMap::<String, Vec<(String, Event<'static>)>>::new();
+  for (person, schedule_to_scene_entries) in person_to_scene_and_schedule_entry {
+    let events = create_person_events(
+      person,
+      schedule_to_scene_entries,
+      participants,
+      config,
+      default_location,
+      language,
+    )?;
+    collections
+      .entry(collection_url(&caldav.url, &config.production_name, person))
+      .or_default()
+      .extend(events);
+  }
+  for (collection, events) in collections {
+    client.sync(&collection, events)?;
+  }
+  Ok(())
+}
+
+fn collection_url(template: &str, production: &str, person: &str) -> String {
+  let mut url = template
+    .replace(
+      "{production}",
+      &utf8_percent_encode(production, NON_ALPHANUMERIC).to_string(),
+    )
+    .replace(
+      "{person}",
+      &utf8_percent_encode(person, NON_ALPHANUMERIC).to_string(),
+    );
+  if !url.ends_with('/') {
+    url.push('/');
+  }
+  url
+}
+
+struct CalDavClient {
+  agent: ureq::Agent,
+  authorization: Option<String>,
+}
+
+impl CalDavClient {
+  fn new(caldav: &CalDavConfig) -> Self {
+    let authorization = caldav.username.as_ref().map(|username| {
+      let credentials = format!(
+        "{}:{}",
+        username,
+        caldav.password.as_deref().unwrap_or_default()
+      );
+      format!(
+        "Basic {}",
+        base64::engine::general_purpose::STANDARD.encode(credentials)
+      )
+    });
+    Self {
+      agent: ureq::AgentBuilder::new().build(),
+      authorization,
+    }
+  }
+
+  /// Uploads the events and deletes our other resources. Of several events
+  /// with the same UID, e.g. of a person with two roles in an entry, only the
+  /// first one is uploaded.
+  fn sync(
+    &self,
+    collection: &str,
+    events: Vec<(String, Event<'static>)>,
+  ) -> Result<(), SceneSchedulerError> {
+    let existing = self.list_own_resources(collection)?;
+    let mut uploaded = HashSet::new();
+    for (uid, event) in events {
+      let resource = format!("{}.ics", uid);
+      if !uploaded.insert(resource.clone()) {
+        continue;
+      }
+      self.send(
+        self
+          .request("PUT", &format!("{}{}", collection, resource))
+          .set("Content-Type", "text/calendar; charset=utf-8"),
+        &single_event_calendar(event).to_string(),
+      )?;
+    }
+    for resource in existing.difference(&uploaded) {
+      self.send(
+        self.request("DELETE", &format!("{}{}", collection, resource)),
+        "",
+      )?;
+    }
+    Ok(())
+  }
+
+  /// Names of the event resources in the collection which were written by
+  /// us. Creates the collection if it does not exist.
+  fn list_own_resources(&self, collection: &str) -> Result<HashSet<String>, SceneSchedulerError> {
+    let response = self
+      .request("PROPFIND", collection)
+      .set("Depth", "1")
+      .set("Content-Type", "application/xml; charset=utf-8")
+      .send_string(PROPFIND_BODY);
+    let body = match response {
+      Ok(response) => response.into_string()?,
+      Err(ureq::Error::Status(404, _)) => {
+        self.send(self.request("MKCALENDAR", collection), "")?;
+        return Ok(HashSet::new());
+      }
+      Err(e) => return Err(caldav_error(collection, e)),
+    };
+    Ok(
+      HREF
+        .captures_iter(&body)
+        .filter_map(|captures| {
+          let href = captures[1].trim().trim_end_matches('/');
+          href.rsplit('/').next().map(str::to_owned)
+        })
+        .filter(|name| OWN_RESOURCE.is_match(name))
+        .collect(),
+    )
+  }
+
+  fn request(&self, method: &str, url: &str) -> ureq::Request {
+    let request = self.agent.request(method, url);
+    match &self.authorization {
+      Some(authorization) => request.set("Authorization", authorization),
+      None => request,
+    }
+  }
+
+  fn send(&self, request: ureq::Request, body: &str) -> Result<(), SceneSchedulerError> {
+    let url = request.url().to_owned();
+    request
+      .send_string(body)
+      .map(|_| ())
+      .map_err(|e| caldav_error(&url, e))
+  }
+}
+
+fn caldav_error(url: &str, error: ureq::Error) -> SceneSchedulerError {
+  SceneSchedulerError::CalDav {
+    message: Phrase::CalDavRequestFailed,
+    url: url.to_owned(),
+    error: error.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::BTreeMap;
+  use std::sync::{Arc, Mutex};
+
+  /// Resources of the collection, `None` until it is created.
+  type Collection = Arc<Mutex<Option<BTreeMap<String, String>>>>;
+
+  /// Minimal CalDAV server keeping the resources of one collection in memory.
+  fn stand_in_caldav_server() -> (String, Collection) {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/anna/", server.server_addr().to_ip().unwrap());
+    let collection = Arc::new(Mutex::new(None::<BTreeMap<String, String>>));
+    let state = collection.clone();
+    std::thread::spawn(move || {
+      for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        request.as_reader().read_to_string(&mut body).unwrap();
+        let name = request.url().trim_start_matches("/anna/").to_owned();
+        let mut collection = state.lock().unwrap();
+        let (status, response) = match (request.method().as_str(), collection.as_mut()) {
+          ("MKCALENDAR", None) => {
+            *collection = Some(BTreeMap::new());
+            (201, String::new())
+          }
+          ("PROPFIND", Some(resources)) => {
+            let hrefs = resources
+              .keys()
+              .map(|name| format!("<D:response><D:href>/anna/{}</D:href></D:response>", name))
+              .collect::<String>();
+            (
+              207,
+              format!(
+                "<D:multistatus xmlns:D=\"DAV:\"><D:response><D:href>/anna/</D:href></D:response>{}</D:multistatus>",
+                hrefs
+              ),
+            )
+          }
+          ("PUT", Some(resources)) => {
+            resources.insert(name, body);
+            (201, String::new())
+          }
+          ("DELETE", Some(resources)) => match resources.remove(&name) {
+            Some(_) => (204, String::new()),
+            None => (404, String::new()),
+          },
+          _ => (404, String::new()),
+        };
+        request
+          .respond(tiny_http::Response::from_string(response).with_status_code(status))
+          .unwrap();
+      }
+    });
+    (url, collection)
+  }
+
+  fn event(uid: &str) -> (String, Event<'static>) {
+    (
+      uid.to_string(),
+      Event::new(uid.to_string(), "20220501T100000Z".to_string()),
+    )
+  }
+
+  #[test]
+  fn test_sync_updates_and_deletes_events() {
+    let (url, collection) = stand_in_caldav_server();
+    let client = CalDavClient::new(&CalDavConfig {
+      url: url.clone(),
+      username: None,
+      password: None,
+    });
+    let uid_1 = "0".repeat(32);
+    let uid_2 = "1".repeat(32);
+    client
+      .sync(&url, vec![event(&uid_1), event(&uid_2)])
+      .unwrap();
+    collection
+      .lock()
+      .unwrap()
+      .as_mut()
+      .unwrap()
+      .insert("foreign.ics".to_string(), String::new());
+
+    client.sync(&url, vec![event(&uid_1)]).unwrap();
+    let names = collection
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap()
+      .keys()
+      .cloned()
+      .collect::<Vec<_>>();
+    assert_eq!(
+      names,
+      vec![format!("{}.ics", uid_1), "foreign.ics".to_string()]
+    );
+  }
+
+  #[test]
+  fn test_sync_two_people_into_one_collection() {
+    use crate::structures::{SceneEntry, Scenes, ScheduleEntry};
+    use chrono::{NaiveDate, NaiveTime};
+
+    let (url, collection) = stand_in_caldav_server();
+    let schedule_entry = |hour| {
+      ScheduleEntry::new(
+        NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+        (NaiveTime::from_hms_opt(hour, 0, 0).unwrap(), None),
+        Scenes::Normal(vec!["1".to_string(), "2".to_string()]),
+        None,
+        None,
+      )
+    };
+    let (morning, evening) = (schedule_entry(10), schedule_entry(19));
+    let role = |role: &str, who: &str, scene: &str| {
+      SceneEntry::new(
+        role.to_string(),
+        who.to_string(),
+        vec![scene.to_string()],
+        vec![false],
+      )
+    };
+    let (faust, gretchen, marthe) = (
+      role("Faust", "Anna", "1"),
+      role("Gretchen", "Bert", "1"),
+      role("Marthe", "Bert", "2"),
+    );
+    let calls = [
+      (&morning, Some(&faust)),
+      (&evening, Some(&gretchen)),
+      (&evening, Some(&marthe)),
+    ];
+    let person_to_scene_and_schedule_entry = vec![
+      ("Anna".to_string(), vec![&calls[0]]),
+      ("Bert".to_string(), vec![&calls[1], &calls[2]]),
+    ];
+    let config = Config::default();
+    let participants = Participants::new(&person_to_scene_and_schedule_entry, &[], &[], &config);
+    push_to_caldav(
+      &person_to_scene_and_schedule_entry,
+      &participants,
+      &CalDavConfig {
+        url,
+        username: None,
+        password: None,
+      },
+      &config,
+      "Theater",
+      Language::German,
+    )
+    .unwrap();
+    let mut names = collection
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap()
+      .keys()
+      .cloned()
+      .collect::<Vec<_>>();
+    let mut expected = vec![
+      format!("{:x}.ics", morning.uuid),
+      format!("{:x}.ics", evening.uuid),
+    ];
+    names.sort();
+    expected.sort();
+    assert_eq!(names, expected);
+  }
+
+  /// Runs against a real server, e.g. `radicale --auth-type none` and
+  /// `SCENE_SCHEDULER_CALDAV_URL=http://localhost:5232/test/scheduler/`.
+  #[test]
+  #[ignore]
+  fn test_sync_with_caldav_server() {
+    let url = std::env::var("SCENE_SCHEDULER_CALDAV_URL").unwrap();
+    let client = CalDavClient::new(&CalDavConfig {
+      url: url.clone(),
+      username: std::env::var("SCENE_SCHEDULER_CALDAV_USER").ok(),
+      password: std::env::var("SCENE_SCHEDULER_CALDAV_PASSWORD").ok(),
+    });
+    let uid = "2".repeat(32);
+    client.sync(&url, vec![event(&uid)]).unwrap();
+    assert!(client
+      .list_own_resources(&url)
+      .unwrap()
+      .contains(&format!("{}.ics", uid)));
+    client.sync(&url, vec![]).unwrap();
+    assert!(client.list_own_resources(&url).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_collection_url() {
+    assert_eq!(
+      collection_url("http://dav/{production}/{person}", "Faust", "Anna M."),
+      "http://dav/Faust/Anna%20M%2E/"
+    );
+  }
+}
@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-use crate::structures::SceneSchedulerError;
+use crate::caldav::CalDavConfig;
+use crate::i18n::Language;
+use crate::mail::MailConfig;
+use crate::structures::{
+  CalendarImport, Casting, CrewMember, EntryCategory, EntryType, Person, PersonInfo, SceneGroup,
+  SceneSchedulerError, Venue,
+};
+use crate::template::EventTemplates;
 
 pub const SCENE_MARK: &str = "x";
 pub const SILENT_PLAY_MARK: &str = "s";
@@ -8,14 +15,137 @@ pub const SILENT_PLAY_MARK: &str = "s";
 pub const GUI_CONFIG_FILE: &str = "gui_config.json";
 pub const GUI_TITLE: &str = "Scene Scheduler";
 
+pub const MASTER_CALENDAR_NAME: &str = "master";
+pub const ROOM_CALENDAR_DIR: &str = "rooms";
+pub const SCENE_CALENDAR_DIR: &str = "scenes";
+pub const EMAIL_DIR: &str = "emails";
+pub const PERSON_PDF_DIR: &str = "pdf";
+pub const CALL_SHEET_DIR: &str = "call_sheets";
+pub const HTML_DIR: &str = "html";
+pub const PERSON_PAGE_DIR: &str = "people";
+pub const DEFAULT_FILE_NAME_TEMPLATE: &str = "{person}.ics";
+
 const CONFIG_FILE: &str = "config.json";
+const DEFAULT_SERVE_ADDRESS: &str = "127.0.0.1:8080";
+const DEFAULT_EVENT_DURATION_MINUTES: u32 = 240;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
   pub excel_file_path: String,
   pub schedule_sheet_num: usize,
   pub scene_sheet_num: usize,
+  #[serde(default)]
+  pub groups_sheet_num: Option<usize>,
+  #[serde(default)]
+  pub people_sheet_num: Option<usize>,
+  #[serde(default)]
+  pub venues_sheet_num: Option<usize>,
   pub out_dir: String,
+  /// Write every export into a new subdirectory of `out_dir` named after the
+  /// current time. Otherwise files of the previous export which are not
+  /// written again are removed.
+  #[serde(default)]
+  pub timestamped_out_dir: bool,
+  /// File name of the calendar of a person. Supports the placeholders
+  /// `{production}` and `{person}`.
+  #[serde(default = "default_file_name_template")]
+  pub file_name_template: String,
+  #[serde(default = "default_production_name")]
+  pub production_name: String,
+  /// Templates for the exported events. If not set, the default templates of
+  /// the chosen language are used.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub event_templates: Option<EventTemplates>,
+  #[serde(default)]
+  pub export_master_calendar: bool,
+  #[serde(default)]
+  pub export_room_calendars: bool,
+  #[serde(default)]
+  pub export_scene_calendars: bool,
+  #[serde(default)]
+  pub export_emails: bool,
+  #[serde(default)]
+  pub mail: MailConfig,
+  #[serde(default)]
+  pub export_caldav: bool,
+  /// Schedules per person and call sheets per schedule entry for printing.
+  #[serde(default)]
+  pub export_pdf: bool,
+  /// Static website with the schedule, to be uploaded to any web host.
+  #[serde(default)]
+  pub export_html: bool,
+  /// Workbook with people as rows and dates as columns, written next to the
+  /// excel file.
+  #[serde(default)]
+  pub export_attendance: bool,
+  /// Everything read from the excel file as JSON. Such a file can also be
+  /// used instead of the excel file.
+  #[serde(default)]
+  pub export_json: bool,
+  /// Server to which the calendars of the people are uploaded.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub caldav: Option<CalDavConfig>,
+  /// Additional calendars which are exported together with the scene
+  /// calendars.
+  #[serde(default)]
+  pub scene_groups: Vec<SceneGroup>,
+  /// Crew members who get a calendar alongside the actors.
+  #[serde(default)]
+  pub crew: Vec<CrewMember>,
+  /// Calendars whose events are added to or replace the schedule sheet.
+  #[serde(default)]
+  pub calendar_imports: Vec<CalendarImport>,
+  /// Rotations and understudies of double cast roles.
+  #[serde(default)]
+  pub castings: Vec<Casting>,
+  /// Contact details in addition to the ones of the people sheet.
+  #[serde(default)]
+  pub people: Vec<PersonInfo>,
+  /// Addresses in addition to the ones of the venues sheet.
+  #[serde(default)]
+  pub venues: Vec<Venue>,
+  /// How rehearsals, dress rehearsals, performances and meetings are
+  /// recognized and treated.
+  #[serde(default = "EntryCategory::defaults")]
+  pub entry_categories: Vec<EntryCategory>,
+  /// Entries without an end time end when the next entry of the same day
+  /// starts. The types may decide otherwise.
+  #[serde(default = "default_end_at_next_entry")]
+  pub end_at_next_entry: bool,
+  /// Length in minutes of entries which have no end time after all, unless
+  /// their type has its own.
+  #[serde(default = "default_event_duration")]
+  pub default_duration: u32,
+  /// Organizer of all events. The email address is taken from the people.
+  #[serde(default)]
+  pub production_manager: Option<Person>,
+  /// Address of the feed server started with `scene-scheduler serve`.
+  #[serde(default = "default_serve_address")]
+  pub serve_address: String,
+  /// Secret from which the tokens in the feed links are derived. It is
+  /// generated when the server is started for the first time.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub feed_secret: Option<String>,
+}
+
+fn default_production_name() -> String {
+  "Theater".to_owned()
+}
+
+fn default_serve_address() -> String {
+  DEFAULT_SERVE_ADDRESS.to_owned()
+}
+
+fn default_end_at_next_entry() -> bool {
+  true
+}
+
+fn default_event_duration() -> u32 {
+  DEFAULT_EVENT_DURATION_MINUTES
+}
+
+fn default_file_name_template() -> String {
+  DEFAULT_FILE_NAME_TEMPLATE.to_owned()
 }
 
 impl Config {
@@ -37,12 +167,73 @@ impl Config {
     Ok(())
   }
 
+  pub fn event_templates(&self, language: Language) -> EventTemplates {
+    self
+      .event_templates
+      .clone()
+      .unwrap_or_else(|| EventTemplates::for_language(language))
+  }
+
+  /// The settings of a type, if it is configured.
+  pub fn entry_category(&self, entry_type: EntryType) -> Option<&EntryCategory> {
+    self
+      .entry_categories
+      .iter()
+      .find(|category| category.entry_type == entry_type)
+  }
+
+  /// Whether entries of the type without an end time end at the next entry.
+  pub fn ends_at_next_entry(&self, entry_type: EntryType) -> bool {
+    self
+      .entry_category(entry_type)
+      .and_then(|category| category.end_at_next_entry)
+      .unwrap_or(self.end_at_next_entry)
+  }
+
+  /// Length in minutes of entries of the type which have no end time.
+  pub fn default_duration(&self, entry_type: EntryType) -> u32 {
+    self
+      .entry_category(entry_type)
+      .and_then(|category| category.default_duration)
+      .unwrap_or(self.default_duration)
+  }
+
   pub fn default() -> Self {
     Self {
       excel_file_path: "".to_owned(),
       schedule_sheet_num: 0,
       scene_sheet_num: 1,
+      groups_sheet_num: None,
+      people_sheet_num: None,
+      venues_sheet_num: None,
       out_dir: "".to_owned(),
+      timestamped_out_dir: false,
+      file_name_template: default_file_name_template(),
+      production_name: default_production_name(),
+      event_templates: None,
+      export_master_calendar: false,
+      export_room_calendars: false,
+      export_scene_calendars: false,
+      export_emails: false,
+      mail: MailConfig::default(),
+      export_caldav: false,
+      export_pdf: false,
+      export_html: false,
+      export_attendance: false,
+      export_json: false,
+      caldav: None,
+      scene_groups: vec![],
+      crew: vec![],
+      calendar_imports: vec![],
+      castings: vec![],
+      people: vec![],
+      venues: vec![],
+      entry_categories: EntryCategory::defaults(),
+      end_at_next_entry: default_end_at_next_entry(),
+      default_duration: default_event_duration(),
+      production_manager: None,
+      serve_address: default_serve_address(),
+      feed_secret: None,
     }
   }
 }
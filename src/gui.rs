@@ -1,9 +1,10 @@
 use crate::config::{Config, GUI_CONFIG_FILE, GUI_TITLE};
+use crate::i18n::{fill, Language, Phrase};
 use crate::scheduler::Scheduler;
 use crate::structures::{SceneSchedulerError, ThemeType};
 use iced::theme::Theme;
 use iced::widget::{
-  button, column, container, horizontal_rule, radio, row, scrollable, text, text_input,
+  button, checkbox, column, container, horizontal_rule, radio, row, scrollable, text, text_input,
 };
 use iced::{alignment, Color, Element, Length, Sandbox};
 use native_dialog::{FileDialog, MessageDialog, MessageType};
@@ -12,12 +13,27 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone)]
 pub enum Message {
   ThemeChanged(ThemeType),
+  LanguageChanged(Language),
+  ProductionNameChanged(String),
   ExcelPathChanged(String),
   ChooseExcelFile,
   OutDirChanged(String),
   ChooseOutDir,
   ScheduleSheetNumChanged(String),
   SceneSheetNumChanged(String),
+  GroupsSheetNumChanged(String),
+  PeopleSheetNumChanged(String),
+  VenuesSheetNumChanged(String),
+  ExportMasterCalendarToggled(bool),
+  ExportRoomCalendarsToggled(bool),
+  ExportSceneCalendarsToggled(bool),
+  ExportEmailsToggled(bool),
+  ExportCalDavToggled(bool),
+  ExportPdfToggled(bool),
+  ExportHtmlToggled(bool),
+  ExportAttendanceToggled(bool),
+  ExportJsonToggled(bool),
+  TimestampedOutDirToggled(bool),
   RunProgram,
   CloseProgram,
 }
@@ -25,6 +41,8 @@ pub enum Message {
 #[derive(Serialize, Deserialize)]
 pub struct GuiConfig {
   pub theme: ThemeType,
+  #[serde(default)]
+  pub language: Language,
 }
 
 impl GuiConfig {
@@ -49,6 +67,7 @@ impl GuiConfig {
   pub fn default() -> Self {
     Self {
       theme: ThemeType::Dark,
+      language: Language::default(),
     }
   }
 }
@@ -92,6 +111,7 @@ impl Sandbox for Gui {
   }
 
   fn update(&mut self, message: Message) {
+    let language = self.gui_config.language;
     match message {
       Message::CloseProgram => {
         let res_config = self.scheduler.config.save();
@@ -105,29 +125,55 @@ impl Sandbox for Gui {
         std::process::exit(0);
       }
       Message::ThemeChanged(theme) => self.gui_config.theme = theme,
+      Message::LanguageChanged(language) => self.gui_config.language = language,
+      Message::ProductionNameChanged(value) => self.scheduler.config.production_name = value,
       Message::ExcelPathChanged(value) => self.scheduler.config.excel_file_path = value,
       Message::OutDirChanged(value) => self.scheduler.config.out_dir = value,
+      Message::ExportMasterCalendarToggled(value) => {
+        self.scheduler.config.export_master_calendar = value
+      }
+      Message::ExportRoomCalendarsToggled(value) => {
+        self.scheduler.config.export_room_calendars = value
+      }
+      Message::ExportSceneCalendarsToggled(value) => {
+        self.scheduler.config.export_scene_calendars = value
+      }
+      Message::ExportEmailsToggled(value) => self.scheduler.config.export_emails = value,
+      Message::ExportCalDavToggled(value) => self.scheduler.config.export_caldav = value,
+      Message::ExportPdfToggled(value) => self.scheduler.config.export_pdf = value,
+      Message::ExportHtmlToggled(value) => self.scheduler.config.export_html = value,
+      Message::ExportAttendanceToggled(value) => self.scheduler.config.export_attendance = value,
+      Message::ExportJsonToggled(value) => self.scheduler.config.export_json = value,
+      Message::TimestampedOutDirToggled(value) => self.scheduler.config.timestamped_out_dir = value,
       Message::ChooseExcelFile => {
-        let start_path = dirs::desktop_dir().or_else(dirs::home_dir).map(|p| p.as_path().to_owned());
+        let start_path = dirs::desktop_dir()
+          .or_else(dirs::home_dir)
+          .map(|p| p.as_path().to_owned());
         let path = if let Some(start_path) = start_path {
           FileDialog::new()
             .set_location(&start_path)
-            .add_filter("Excel File", &["xlsx"])
+            .add_filter(
+              language.tr(Phrase::ExcelFileFilter),
+              &["xlsx", "json", "plan"],
+            )
             .show_open_single_file()
         } else {
           println!("Error: Could not find desktop directory");
           FileDialog::new()
-            .add_filter("Excel File", &["xlsx"])
+            .add_filter(
+              language.tr(Phrase::ExcelFileFilter),
+              &["xlsx", "json", "plan"],
+            )
             .show_open_single_file()
         };
 
         if path.is_err() {
           _ = MessageDialog::new()
             .set_type(MessageType::Error)
-            .set_title("Error")
-            .set_text(&format!(
-              "Could not set excel file path: {}",
-              path.as_ref().err().unwrap()
+            .set_title(language.tr(Phrase::ErrorTitle))
+            .set_text(&fill(
+              language.tr(Phrase::CouldNotSetExcelPath),
+              &[("error", path.as_ref().err().unwrap())],
             ))
             .show_alert();
         }
@@ -150,10 +196,10 @@ impl Sandbox for Gui {
         if path.is_err() {
           _ = MessageDialog::new()
             .set_type(MessageType::Error)
-            .set_title("Error")
-            .set_text(&format!(
-              "Could not set out dir : {}",
-              path.as_ref().err().unwrap()
+            .set_title(language.tr(Phrase::ErrorTitle))
+            .set_text(&fill(
+              language.tr(Phrase::CouldNotSetOutDir),
+              &[("error", path.as_ref().err().unwrap())],
             ))
             .show_alert();
         }
@@ -163,21 +209,44 @@ impl Sandbox for Gui {
         }
       }
       Message::RunProgram => {
-        let res = self.scheduler.process();
+        let res = self.scheduler.process(language);
         match res {
-          Ok(_) => {
+          Ok(export) => {
+            let mut text = language.tr(Phrase::SuccessText).to_owned();
+            if !export.without_email.is_empty() {
+              text.push('\n');
+              text.push_str(&fill(
+                language.tr(Phrase::NoEmailAddress),
+                &[("people", &export.without_email.join(", "))],
+              ));
+            }
+            if !export.overwritten.is_empty() {
+              let files = export
+                .overwritten
+                .iter()
+                .map(|file| file.to_string_lossy())
+                .collect::<Vec<_>>();
+              text.push('\n');
+              text.push_str(&fill(
+                language.tr(Phrase::OverwrittenFiles),
+                &[("files", &files.join(", "))],
+              ));
+            }
             _ = MessageDialog::new()
               .set_type(MessageType::Info)
-              .set_title("Hurra!")
-              .set_text("Alle ICS Dateien wurden generiert!")
+              .set_title(language.tr(Phrase::SuccessTitle))
+              .set_text(&text)
               .show_alert();
           }
           Err(e) => {
             println!("Error: {}", e);
             _ = MessageDialog::new()
               .set_type(MessageType::Error)
-              .set_title("Error")
-              .set_text(&format!("Could not generate ics files: {}", e))
+              .set_title(language.tr(Phrase::ErrorTitle))
+              .set_text(&fill(
+                language.tr(Phrase::CouldNotGenerate),
+                &[("error", &e.localized(language))],
+              ))
               .show_alert();
           }
         }
@@ -193,10 +262,10 @@ impl Sandbox for Gui {
             println!("Could not parse ScheduleSheetNum: {}", err);
             _ = MessageDialog::new()
               .set_type(MessageType::Error)
-              .set_title("Error")
-              .set_text(&format!(
-                "Wrong schedule sheet number entry. Must be a positiv integer but found: {}",
-                value
+              .set_title(language.tr(Phrase::ErrorTitle))
+              .set_text(&fill(
+                language.tr(Phrase::WrongScheduleSheetNum),
+                &[("value", &value)],
               ))
               .show_alert();
           }
@@ -217,10 +286,10 @@ impl Sandbox for Gui {
             println!("Could not parse ScheduleSheetNum: {}", err);
             _ = MessageDialog::new()
               .set_type(MessageType::Error)
-              .set_title("Error")
-              .set_text(&format!(
-                "Wrong scene sheet number entry. Must be a positiv integer but found: {}",
-                value
+              .set_title(language.tr(Phrase::ErrorTitle))
+              .set_text(&fill(
+                language.tr(Phrase::WrongSceneSheetNum),
+                &[("value", &value)],
               ))
               .show_alert();
           }
@@ -230,15 +299,82 @@ impl Sandbox for Gui {
           }
         }
       }
+      Message::GroupsSheetNumChanged(value) => {
+        if value.is_empty() {
+          // Empty input: there is no groups sheet.
+          self.scheduler.config.groups_sheet_num = None;
+          return;
+        }
+        match value.parse::<usize>() {
+          Err(err) => {
+            println!("Could not parse GroupsSheetNum: {}", err);
+            _ = MessageDialog::new()
+              .set_type(MessageType::Error)
+              .set_title(language.tr(Phrase::ErrorTitle))
+              .set_text(&fill(
+                language.tr(Phrase::WrongGroupsSheetNum),
+                &[("value", &value)],
+              ))
+              .show_alert();
+          }
+          Ok(value_usize) => self.scheduler.config.groups_sheet_num = Some(value_usize),
+        }
+      }
+      Message::PeopleSheetNumChanged(value) => {
+        if value.is_empty() {
+          // Empty input: there is no people sheet.
+          self.scheduler.config.people_sheet_num = None;
+          return;
+        }
+        match value.parse::<usize>() {
+          Err(err) => {
+            println!("Could not parse PeopleSheetNum: {}", err);
+            _ = MessageDialog::new()
+              .set_type(MessageType::Error)
+              .set_title(language.tr(Phrase::ErrorTitle))
+              .set_text(&fill(
+                language.tr(Phrase::WrongPeopleSheetNum),
+                &[("value", &value)],
+              ))
+              .show_alert();
+          }
+          Ok(value_usize) => self.scheduler.config.people_sheet_num = Some(value_usize),
+        }
+      }
+      Message::VenuesSheetNumChanged(value) => {
+        if value.is_empty() {
+          // Empty input: there is no venues sheet.
+          self.scheduler.config.venues_sheet_num = None;
+          return;
+        }
+        match value.parse::<usize>() {
+          Err(err) => {
+            println!("Could not parse VenuesSheetNum: {}", err);
+            _ = MessageDialog::new()
+              .set_type(MessageType::Error)
+              .set_title(language.tr(Phrase::ErrorTitle))
+              .set_text(&fill(
+                language.tr(Phrase::WrongVenuesSheetNum),
+                &[("value", &value)],
+              ))
+              .show_alert();
+          }
+          Ok(value_usize) => self.scheduler.config.venues_sheet_num = Some(value_usize),
+        }
+      }
     }
   }
 
-  fn view(&self) -> Element<Message> {
+  fn view(&self) -> Element<'_, Message> {
+    let language = self.gui_config.language;
     let choose_theme = [ThemeType::Light, ThemeType::Dark].iter().fold(
-      column![text("Farbschema:")].spacing(10),
+      column![text(language.tr(Phrase::ColorScheme))].spacing(10),
       |column, theme| {
         column.push(radio(
-          format!("{theme:?}"),
+          language.tr(match theme {
+            ThemeType::Light => Phrase::ThemeLight,
+            ThemeType::Dark => Phrase::ThemeDark,
+          }),
           *theme,
           if self.gui_config.theme == *theme {
             Some(*theme)
@@ -250,27 +386,50 @@ impl Sandbox for Gui {
       },
     );
 
+    let choose_language = Language::ALL.iter().fold(
+      column![text(language.tr(Phrase::LanguageLabel))].spacing(10),
+      |column, choice| {
+        column.push(radio(
+          choice.name(),
+          *choice,
+          if language == *choice {
+            Some(*choice)
+          } else {
+            None
+          },
+          Message::LanguageChanged,
+        ))
+      },
+    );
+
     let title = text(GUI_TITLE)
       .width(Length::Fill)
       .size(30)
       .style(Color::from([0.5, 0.5, 0.5]))
       .horizontal_alignment(alignment::Horizontal::Center);
 
-    let excel_file_path_input =
-      text_input("Szenenplan Excel", &self.scheduler.config.excel_file_path)
-        .on_input(Message::ExcelPathChanged)
-        .padding(10)
-        .size(20);
+    let production_name_input = text_input(
+      language.tr(Phrase::ProductionName),
+      &self.scheduler.config.production_name,
+    )
+    .on_input(Message::ProductionNameChanged)
+    .padding(10)
+    .size(20);
 
-    let out_dir_input = text_input(
-      "Ausgabeordner für ICS Dateien",
-      &self.scheduler.config.out_dir,
+    let excel_file_path_input = text_input(
+      language.tr(Phrase::ExcelFile),
+      &self.scheduler.config.excel_file_path,
     )
-    .on_input(Message::OutDirChanged)
+    .on_input(Message::ExcelPathChanged)
     .padding(10)
     .size(20);
 
-    let scene_sheet_num_label = text("Arbeitsblatt Nummer für den Einsatzplan:")
+    let out_dir_input = text_input(language.tr(Phrase::OutDir), &self.scheduler.config.out_dir)
+      .on_input(Message::OutDirChanged)
+      .padding(10)
+      .size(20);
+
+    let scene_sheet_num_label = text(language.tr(Phrase::SceneSheetNumLabel))
       .width(Length::Fill)
       .size(15)
       .style(Color::from([0.5, 0.5, 0.5]))
@@ -279,12 +438,66 @@ impl Sandbox for Gui {
       Some(num) => num.to_string(),
       None => "".to_string(),
     };
-    let scene_sheet_num_input = text_input("Einsatzplan Nummer", &scene_sheet_num_input_value)
-      .on_input(Message::SceneSheetNumChanged)
-      .padding(10)
-      .size(20);
+    let scene_sheet_num_input = text_input(
+      language.tr(Phrase::SceneSheetNum),
+      &scene_sheet_num_input_value,
+    )
+    .on_input(Message::SceneSheetNumChanged)
+    .padding(10)
+    .size(20);
 
-    let schedule_sheet_num_label = text("Arbeitsblatt Nummer für den Terminplan:")
+    let groups_sheet_num_label = text(language.tr(Phrase::GroupsSheetNumLabel))
+      .width(Length::Fill)
+      .size(15)
+      .style(Color::from([0.5, 0.5, 0.5]))
+      .horizontal_alignment(alignment::Horizontal::Left);
+    let groups_sheet_num_input_value = match self.scheduler.config.groups_sheet_num {
+      Some(num) => num.to_string(),
+      None => "".to_string(),
+    };
+    let groups_sheet_num_input = text_input(
+      language.tr(Phrase::GroupsSheetNum),
+      &groups_sheet_num_input_value,
+    )
+    .on_input(Message::GroupsSheetNumChanged)
+    .padding(10)
+    .size(20);
+
+    let people_sheet_num_label = text(language.tr(Phrase::PeopleSheetNumLabel))
+      .width(Length::Fill)
+      .size(15)
+      .style(Color::from([0.5, 0.5, 0.5]))
+      .horizontal_alignment(alignment::Horizontal::Left);
+    let people_sheet_num_input_value = match self.scheduler.config.people_sheet_num {
+      Some(num) => num.to_string(),
+      None => "".to_string(),
+    };
+    let people_sheet_num_input = text_input(
+      language.tr(Phrase::PeopleSheetNum),
+      &people_sheet_num_input_value,
+    )
+    .on_input(Message::PeopleSheetNumChanged)
+    .padding(10)
+    .size(20);
+
+    let venues_sheet_num_label = text(language.tr(Phrase::VenuesSheetNumLabel))
+      .width(Length::Fill)
+      .size(15)
+      .style(Color::from([0.5, 0.5, 0.5]))
+      .horizontal_alignment(alignment::Horizontal::Left);
+    let venues_sheet_num_input_value = match self.scheduler.config.venues_sheet_num {
+      Some(num) => num.to_string(),
+      None => "".to_string(),
+    };
+    let venues_sheet_num_input = text_input(
+      language.tr(Phrase::VenuesSheetNum),
+      &venues_sheet_num_input_value,
+    )
+    .on_input(Message::VenuesSheetNumChanged)
+    .padding(10)
+    .size(20);
+
+    let schedule_sheet_num_label = text(language.tr(Phrase::ScheduleSheetNumLabel))
       .width(Length::Fill)
       .size(15)
       .style(Color::from([0.5, 0.5, 0.5]))
@@ -294,38 +507,131 @@ impl Sandbox for Gui {
       Some(num) => num.to_string(),
       None => "".to_string(),
     };
-    let schedule_sheet_num_input = text_input("Terminplan Nummer", &schedule_sheet_num_input_value)
-      .on_input(Message::ScheduleSheetNumChanged)
-      .padding(10)
-      .size(20);
+    let schedule_sheet_num_input = text_input(
+      language.tr(Phrase::ScheduleSheetNum),
+      &schedule_sheet_num_input_value,
+    )
+    .on_input(Message::ScheduleSheetNumChanged)
+    .padding(10)
+    .size(20);
+
+    let export_master_calendar_checkbox = checkbox(
+      language.tr(Phrase::ExportMasterCalendar),
+      self.scheduler.config.export_master_calendar,
+      Message::ExportMasterCalendarToggled,
+    );
+
+    let export_room_calendars_checkbox = checkbox(
+      language.tr(Phrase::ExportRoomCalendars),
+      self.scheduler.config.export_room_calendars,
+      Message::ExportRoomCalendarsToggled,
+    );
+
+    let export_scene_calendars_checkbox = checkbox(
+      language.tr(Phrase::ExportSceneCalendars),
+      self.scheduler.config.export_scene_calendars,
+      Message::ExportSceneCalendarsToggled,
+    );
+
+    let export_emails_checkbox = checkbox(
+      language.tr(Phrase::ExportEmails),
+      self.scheduler.config.export_emails,
+      Message::ExportEmailsToggled,
+    );
 
-    let generate_ics_button = button("Generiere ICS Dateine")
+    let export_caldav_checkbox = checkbox(
+      language.tr(Phrase::ExportCalDav),
+      self.scheduler.config.export_caldav,
+      Message::ExportCalDavToggled,
+    );
+
+    let export_pdf_checkbox = checkbox(
+      language.tr(Phrase::ExportPdf),
+      self.scheduler.config.export_pdf,
+      Message::ExportPdfToggled,
+    );
+
+    let export_html_checkbox = checkbox(
+      language.tr(Phrase::ExportHtml),
+      self.scheduler.config.export_html,
+      Message::ExportHtmlToggled,
+    );
+
+    let export_attendance_checkbox = checkbox(
+      language.tr(Phrase::ExportAttendance),
+      self.scheduler.config.export_attendance,
+      Message::ExportAttendanceToggled,
+    );
+
+    let export_json_checkbox = checkbox(
+      language.tr(Phrase::ExportJson),
+      self.scheduler.config.export_json,
+      Message::ExportJsonToggled,
+    );
+
+    let timestamped_out_dir_checkbox = checkbox(
+      language.tr(Phrase::TimestampedOutDir),
+      self.scheduler.config.timestamped_out_dir,
+      Message::TimestampedOutDirToggled,
+    );
+
+    let generate_ics_button = button(language.tr(Phrase::GenerateIcs))
       .padding(10)
       .on_press(Message::RunProgram);
 
-    let choose_excel_file_button = button("Wähle eine Excel Datei")
+    let choose_excel_file_button = button(language.tr(Phrase::ChooseExcelFile))
       .padding(10)
       .on_press(Message::ChooseExcelFile);
 
-    let choose_out_dir_button = button("Wähle ICS Ausgabe Ordner")
+    let choose_out_dir_button = button(language.tr(Phrase::ChooseOutDir))
       .padding(10)
       .on_press(Message::ChooseOutDir);
 
-    let close_button = button("Schliessen")
+    let close_button = button(language.tr(Phrase::Close))
       .padding(10)
       .on_press(Message::CloseProgram);
 
     let content = column![
       title,
+      production_name_input,
       row![excel_file_path_input, choose_excel_file_button].spacing(10),
       row![out_dir_input, choose_out_dir_button].spacing(10),
+      timestamped_out_dir_checkbox,
       column![
         row![schedule_sheet_num_label, scene_sheet_num_label].spacing(10),
         row![schedule_sheet_num_input, scene_sheet_num_input].spacing(10),
       ],
+      column![
+        row![
+          groups_sheet_num_label,
+          people_sheet_num_label,
+          venues_sheet_num_label
+        ]
+        .spacing(10),
+        row![
+          groups_sheet_num_input,
+          people_sheet_num_input,
+          venues_sheet_num_input
+        ]
+        .spacing(10),
+      ],
+      row![
+        export_master_calendar_checkbox,
+        export_room_calendars_checkbox,
+        export_scene_calendars_checkbox,
+      ]
+      .spacing(10),
+      row![
+        export_pdf_checkbox,
+        export_html_checkbox,
+        export_attendance_checkbox,
+        export_json_checkbox,
+      ]
+      .spacing(10),
+      row![export_emails_checkbox, export_caldav_checkbox].spacing(10),
       generate_ics_button,
       horizontal_rule(38),
-      choose_theme,
+      row![choose_theme, choose_language].spacing(40),
       horizontal_rule(38),
       close_button,
     ]
@@ -0,0 +1,581 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::config::{
+  Config, HTML_DIR, MASTER_CALENDAR_NAME, PERSON_PAGE_DIR, ROOM_CALENDAR_DIR, SCENE_CALENDAR_DIR,
+};
+use crate::i18n::{Language, Phrase};
+use crate::ics::{
+  create_cast_calendar, create_master_calendar, create_person_calendar, scene_selections,
+  Participants,
+};
+use crate::output::OutputFiles;
+use crate::sorting::{filter_by_scenes, get_schedule_entry_to_persons};
+use crate::structures::{
+  Person, PersonToSceneAndScheduleEntry, Scene, SceneEntry, SceneSchedulerError, Scenes,
+  ScheduleEntry, ScheduleEntryToCast,
+};
+use crate::template::{format_days, format_stop_time};
+
+/// Characters which are kept as they are in links to the generated files.
+const LINK: &AsciiSet = &NON_ALPHANUMERIC.remove(b'.').remove(b'-').remove(b'_');
+const STYLESHEET_NAME: &str = "style";
+const INDEX_NAME: &str = "index";
+const DATE_FORMAT: &str = "%d.%m.%Y";
+const TIME_FORMAT: &str = "%H:%M";
+
+const STYLESHEET: &str = "body {
+  font-family: sans-serif;
+  margin: 2em auto;
+  max-width: 60em;
+  padding: 0 1em;
+}
+nav, .calendar {
+  margin: 1em 0;
+}
+table {
+  border-collapse: collapse;
+  width: 100%;
+}
+th, td {
+  border-bottom: 1px solid #ccc;
+  padding: 0.3em 0.5em;
+  text-align: left;
+  vertical-align: top;
+}
+th {
+  background: #eee;
+}
+ul.links {
+  columns: 3;
+}
+
+@media print {
+  body {
+    font-size: 10pt;
+    margin: 0;
+    max-width: none;
+  }
+  nav, .calendar, ul.links {
+    display: none;
+  }
+  a {
+    color: inherit;
+    text-decoration: none;
+  }
+  th {
+    background: none;
+  }
+  thead {
+    display: table-header-group;
+  }
+  tr {
+    page-break-inside: avoid;
+  }
+}
+";
+
+/// Writes a static website into the `html` directory: an overview with the
+/// whole schedule and one page per person, room and scene, each next to its
+/// calendar. All links are relative, so the directory can be uploaded as is.
+#[allow(clippy::too_many_arguments)]
+pub fn write_html_site(
+  person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+  schedule_entry_to_cast: &ScheduleEntryToCast,
+  all_scenes: &[Scene],
+  participants: &Participants,
+  output_files: &mut OutputFiles,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<(), SceneSchedulerError> {
+  let person_dir = format!("{}/{}", HTML_DIR, PERSON_PAGE_DIR);
+  let room_dir = format!("{}/{}", HTML_DIR, ROOM_CALENDAR_DIR);
+  let scene_dir = format!("{}/{}", HTML_DIR, SCENE_CALENDAR_DIR);
+
+  let mut room_to_entries: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+  for entry in schedule_entry_to_cast {
+    for room in entry.0.rooms(default_location) {
+      room_to_entries.entry(room).or_default().push(entry);
+    }
+  }
+  let scenes = scene_selections(all_scenes, config);
+
+  // All files are registered first, so every page can link to the others.
+  let mut site = Site {
+    config,
+    default_location,
+    language,
+    persons: HashMap::new(),
+    rooms: HashMap::new(),
+    scenes: HashMap::new(),
+    called: get_schedule_entry_to_persons(person_to_scene_and_schedule_entry)
+      .into_iter()
+      .map(|(schedule_entry, persons)| (schedule_entry.uuid, persons))
+      .collect(),
+  };
+  let mut person_files = vec![];
+  for (person, schedule_to_scene_entries) in person_to_scene_and_schedule_entry {
+    let files = register(output_files, &person_dir, person)?;
+    site
+      .persons
+      .insert(person.clone(), link(PERSON_PAGE_DIR, &files.0));
+    person_files.push((person, schedule_to_scene_entries, files));
+  }
+  let mut room_files = vec![];
+  for (room, entries) in &room_to_entries {
+    let files = register(output_files, &room_dir, room)?;
+    site
+      .rooms
+      .insert(room.to_string(), link(ROOM_CALENDAR_DIR, &files.0));
+    room_files.push((room, entries, files));
+  }
+  let mut scene_files = vec![];
+  for (name, scenes) in &scenes {
+    let files = register(output_files, &scene_dir, name)?;
+    site
+      .scenes
+      .insert(name.to_string(), link(SCENE_CALENDAR_DIR, &files.0));
+    scene_files.push((name, scenes, files));
+  }
+
+  std::fs::write(
+    output_files.file(Some(HTML_DIR), STYLESHEET_NAME, "css")?,
+    STYLESHEET,
+  )?;
+  create_master_calendar(
+    schedule_entry_to_cast,
+    participants,
+    config,
+    default_location,
+    language,
+  )?
+  .save_file(output_files.file(Some(HTML_DIR), MASTER_CALENDAR_NAME, "ics")?)?;
+  std::fs::write(
+    output_files.file(Some(HTML_DIR), INDEX_NAME, "html")?,
+    site.index_page(schedule_entry_to_cast, &scenes),
+  )?;
+
+  for (person, schedule_to_scene_entries, (page, calendar)) in person_files {
+    create_person_calendar(
+      person,
+      schedule_to_scene_entries,
+      participants,
+      config,
+      default_location,
+      language,
+    )?
+    .save_file(&calendar)?;
+    std::fs::write(
+      page,
+      site.person_page(person, schedule_to_scene_entries, &calendar),
+    )?;
+  }
+  for (room, entries, (page, calendar)) in room_files {
+    create_cast_calendar(
+      entries.iter().copied(),
+      Some(room),
+      participants,
+      config,
+      default_location,
+      language,
+    )?
+    .save_file(&calendar)?;
+    std::fs::write(page, site.cast_page(room, entries, &calendar))?;
+  }
+  for (name, scenes, (page, calendar)) in scene_files {
+    let entries = filter_by_scenes(schedule_entry_to_cast, scenes);
+    create_cast_calendar(
+      entries.iter(),
+      None,
+      participants,
+      config,
+      default_location,
+      language,
+    )?
+    .save_file(&calendar)?;
+    std::fs::write(
+      page,
+      site.cast_page(
+        &format!("{} {}", language.tr(Phrase::SceneLabel), name),
+        &entries.iter().collect::<Vec<_>>(),
+        &calendar,
+      ),
+    )?;
+  }
+  Ok(())
+}
+
+/// Paths of the page and the calendar named after a person, room or scene.
+fn register(
+  output_files: &mut OutputFiles,
+  dir: &str,
+  name: &str,
+) -> Result<(std::path::PathBuf, std::path::PathBuf), SceneSchedulerError> {
+  Ok((
+    output_files.file(Some(dir), name, "html")?,
+    output_files.file(Some(dir), name, "ics")?,
+  ))
+}
+
+/// Link to a generated file relative to the root of the site.
+fn link(dir: &str, path: &Path) -> String {
+  format!("{}/{}", dir, file_link(path))
+}
+
+/// Link to a generated file from a page in the same directory.
+fn file_link(path: &Path) -> String {
+  let file_name = path
+    .file_name()
+    .map(|name| name.to_string_lossy())
+    .unwrap_or_default();
+  utf8_percent_encode(&file_name, LINK).to_string()
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+struct Site<'a> {
+  config: &'a Config,
+  default_location: &'a str,
+  language: Language,
+  /// Links to the pages, relative to the root of the site.
+  persons: HashMap<Person, String>,
+  rooms: HashMap<String, String>,
+  scenes: HashMap<String, String>,
+  called: HashMap<md5::Digest, Vec<(&'a Person, Option<&'a SceneEntry>)>>,
+}
+
+impl Site<'_> {
+  fn index_page(
+    &self,
+    schedule_entry_to_cast: &ScheduleEntryToCast,
+    scenes: &[(&str, &[Scene])],
+  ) -> String {
+    let language = self.language;
+    let mut persons = self.persons.keys().map(String::as_str).collect::<Vec<_>>();
+    persons.sort_unstable();
+    let mut rooms = self.rooms.keys().map(String::as_str).collect::<Vec<_>>();
+    rooms.sort_unstable();
+    let scenes = scenes.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    let mut body = calendar_link(
+      &format!("{}.ics", MASTER_CALENDAR_NAME),
+      language.tr(Phrase::CalendarLink),
+    );
+    for (heading, names, links) in [
+      (Phrase::PeopleLabel, persons, &self.persons),
+      (Phrase::RoomsLabel, rooms, &self.rooms),
+      (Phrase::ScenesLabel, scenes, &self.scenes),
+    ] {
+      if names.is_empty() {
+        continue;
+      }
+      body.push_str(&format!(
+        "<h2>{}</h2>\n<ul class=\"links\">\n",
+        language.tr(heading)
+      ));
+      for name in names {
+        body.push_str(&format!("<li>{}</li>\n", self.anchor(name, links, "")));
+      }
+      body.push_str("</ul>\n");
+    }
+    body.push_str(&format!(
+      "<h2>{}</h2>\n",
+      language.tr(Phrase::ScheduleLabel)
+    ));
+    body.push_str(&self.cast_table(&schedule_entry_to_cast.iter().collect::<Vec<_>>(), ""));
+    self.page(&self.config.production_name, "", &body)
+  }
+
+  fn person_page(
+    &self,
+    person: &Person,
+    schedule_to_scene_entries: &[&(&ScheduleEntry, Option<&SceneEntry>)],
+    calendar: &Path,
+  ) -> String {
+    let language = self.language;
+    let root = "../";
+    let mut body = calendar_link(&file_link(calendar), language.tr(Phrase::CalendarLink));
+    body.push_str(&table_head(
+      language,
+      &[
+        Phrase::DateLabel,
+        Phrase::TimeLabel,
+        Phrase::RoomLabel,
+        Phrase::ScenesLabel,
+        Phrase::RoleLabel,
+        Phrase::CallLabel,
+        Phrase::NoteLabel,
+      ],
+    ));
+    for (schedule_entry, scene_entry) in schedule_to_scene_entries {
+      let role = scene_entry
+        .map(|scene_entry| escape(&scene_entry.role))
+        .unwrap_or_default();
+      let call = schedule_entry
+        .call_type_for(*scene_entry, person)
+        .label(language);
+      body.push_str(&self.row(schedule_entry, *scene_entry, &[role, call.to_owned()], root));
+    }
+    body.push_str("</tbody>\n</table>\n");
+    self.page(person, root, &body)
+  }
+
+  fn cast_page(
+    &self,
+    title: &str,
+    entries: &[&(&ScheduleEntry, Vec<&SceneEntry>)],
+    calendar: &Path,
+  ) -> String {
+    let root = "../";
+    let mut body = calendar_link(&file_link(calendar), self.language.tr(Phrase::CalendarLink));
+    body.push_str(&self.cast_table(entries, root));
+    self.page(title, root, &body)
+  }
+
+  fn cast_table(&self, entries: &[&(&ScheduleEntry, Vec<&SceneEntry>)], root: &str) -> String {
+    let mut table = table_head(
+      self.language,
+      &[
+        Phrase::DateLabel,
+        Phrase::TimeLabel,
+        Phrase::RoomLabel,
+        Phrase::ScenesLabel,
+        Phrase::CastLabel,
+        Phrase::NoteLabel,
+      ],
+    );
+    for (schedule_entry, _) in entries {
+      let called = self
+        .called
+        .get(&schedule_entry.uuid)
+        .map(|persons| {
+          persons
+            .iter()
+            .map(|(person, scene_entry)| {
+              let mut text = self.anchor(person, &self.persons, root);
+              if let Some(scene_entry) = scene_entry {
+                text.push_str(&format!(" ({})", escape(&scene_entry.role)));
+              }
+              text
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+        })
+        .unwrap_or_default();
+      table.push_str(&self.row(schedule_entry, None, &[called], root));
+    }
+    table.push_str("</tbody>\n</table>\n");
+    table
+  }
+
+  /// Date, time, room and scenes of the entry, followed by `cells` and the
+  /// note. For a role in a parallel session only its room and scenes are
+  /// shown, otherwise the rooms and scenes of all sessions.
+  fn row(
+    &self,
+    schedule_entry: &ScheduleEntry,
+    scene_entry: Option<&SceneEntry>,
+    cells: &[String],
+    root: &str,
+  ) -> String {
+    let start = schedule_entry.start_stop_date_time().0;
+    let (date, time) = match format_days(schedule_entry) {
+      Some(days) => (days, self.language.tr(Phrase::AllDay).to_owned()),
+      None => {
+        let mut time = start.format(TIME_FORMAT).to_string();
+        if let Some(stop) = format_stop_time(schedule_entry) {
+          time.push_str(&format!("–{}", stop));
+        }
+        (start.format(DATE_FORMAT).to_string(), time)
+      }
+    };
+    let scene_list = |scenes: &[String]| {
+      scenes
+        .iter()
+        .map(|scene| self.anchor(scene, &self.scenes, root))
+        .collect::<Vec<_>>()
+        .join(", ")
+    };
+    let (rooms, scenes) = match schedule_entry.session_for(scene_entry) {
+      Some(session) => (
+        vec![session.room.as_deref().unwrap_or(self.default_location)],
+        scene_list(&session.scenes),
+      ),
+      None if !schedule_entry.sessions.is_empty() => (
+        schedule_entry.rooms(self.default_location),
+        schedule_entry
+          .sessions
+          .iter()
+          .map(|session| scene_list(&session.scenes))
+          .collect::<Vec<_>>()
+          .join("; "),
+      ),
+      None => (
+        vec![schedule_entry
+          .room
+          .as_deref()
+          .unwrap_or(self.default_location)],
+        match &schedule_entry.scenes {
+          Scenes::Normal(scenes) if scenes.is_empty() => {
+            self.language.tr(Phrase::AllScenes).to_owned()
+          }
+          Scenes::Normal(scenes) => scene_list(scenes),
+          Scenes::Special(scene) => escape(scene),
+        },
+      ),
+    };
+    let mut row = vec![
+      date,
+      time,
+      rooms
+        .iter()
+        .map(|room| self.anchor(room, &self.rooms, root))
+        .collect::<Vec<_>>()
+        .join("; "),
+      scenes,
+    ];
+    row.extend_from_slice(cells);
+    row.push(escape(schedule_entry.note.as_deref().unwrap_or_default()));
+    // Entries of a type with a color are marked at the start of their row.
+    let attributes = self
+      .config
+      .entry_category(schedule_entry.entry_type)
+      .and_then(|category| category.color.as_deref())
+      .map(|color| {
+        format!(
+          " title=\"{}\" style=\"border-left: 0.4em solid {}\"",
+          schedule_entry.entry_type.label(self.language),
+          escape(color)
+        )
+      })
+      .unwrap_or_default();
+    format!(
+      "<tr{}>{}</tr>\n",
+      attributes,
+      row
+        .iter()
+        .map(|cell| format!("<td>{}</td>", cell))
+        .collect::<String>()
+    )
+  }
+
+  /// The name, linked to its page if there is one.
+  fn anchor(&self, name: &str, links: &HashMap<String, String>, root: &str) -> String {
+    match links.get(name) {
+      Some(href) => format!("<a href=\"{}{}\">{}</a>", root, href, escape(name)),
+      None => escape(name),
+    }
+  }
+
+  fn page(&self, title: &str, root: &str, body: &str) -> String {
+    let production = escape(&self.config.production_name);
+    let title = escape(title);
+    let head_title = if title == production {
+      production.clone()
+    } else {
+      format!("{} – {}", title, production)
+    };
+    format!(
+      "<!DOCTYPE html>
+<html lang=\"{lang}\">
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{head_title}</title>
+<link rel=\"stylesheet\" href=\"{root}{stylesheet}.css\">
+</head>
+<body>
+<nav><a href=\"{root}{index}.html\">{production}</a></nav>
+<h1>{title}</h1>
+{body}</body>
+</html>
+",
+      lang = self.language.code(),
+      head_title = head_title,
+      title = title,
+      production = production,
+      root = root,
+      stylesheet = STYLESHEET_NAME,
+      index = INDEX_NAME,
+      body = body,
+    )
+  }
+}
+
+fn calendar_link(href: &str, text: &str) -> String {
+  format!(
+    "<p class=\"calendar\"><a href=\"{}\">{}</a></p>\n",
+    href, text
+  )
+}
+
+fn table_head(language: Language, columns: &[Phrase]) -> String {
+  format!(
+    "<table>\n<thead><tr>{}</tr></thead>\n<tbody>\n",
+    columns
+      .iter()
+      .map(|column| format!("<th>{}</th>", language.tr(*column)))
+      .collect::<String>()
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{NaiveDate, NaiveTime};
+
+  #[test]
+  fn test_person_page_links_rooms_and_scenes() {
+    let schedule_entry = ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (
+        NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+        Some(NaiveTime::from_hms_opt(13, 0, 0).unwrap()),
+      ),
+      Scenes::Normal(vec!["3".to_string()]),
+      Some("Probebühne".to_string()),
+      Some("<Kostüm>".to_string()),
+    );
+    let gretchen = SceneEntry::new(
+      "Gretchen".to_string(),
+      "Anna".to_string(),
+      vec!["3".to_string()],
+      vec![false],
+    );
+    let config = Config::default();
+    let site = Site {
+      config: &config,
+      default_location: "Theater",
+      language: Language::English,
+      persons: HashMap::new(),
+      rooms: HashMap::from([(
+        "Probebühne".to_string(),
+        "rooms/Probeb%C3%BChne.html".to_string(),
+      )]),
+      scenes: HashMap::from([("3".to_string(), "scenes/3.html".to_string())]),
+      called: HashMap::new(),
+    };
+    let entry = (&schedule_entry, Some(&gretchen));
+    let page = site.person_page(
+      &"Anna".to_string(),
+      &[&entry],
+      Path::new("html/people/Anna M.ics"),
+    );
+    assert!(page.contains("<a href=\"Anna%20M.ics\">Download calendar (.ics)</a>"));
+    assert!(page.contains(
+      "<tr><td>01.05.2022</td><td>10:00–13:00</td>\
+       <td><a href=\"../rooms/Probeb%C3%BChne.html\">Probebühne</a></td>\
+       <td><a href=\"../scenes/3.html\">3</a></td><td>Gretchen</td><td>Scene rehearsal</td>\
+       <td>&lt;Kostüm&gt;</td></tr>"
+    ));
+    assert!(page.contains("<link rel=\"stylesheet\" href=\"../style.css\">"));
+  }
+}
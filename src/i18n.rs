@@ -0,0 +1,717 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Language {
+  #[default]
+  German,
+  English,
+  French,
+}
+
+/// All texts shown to the user. The catalogs below map every phrase to its
+/// translation. Phrases may contain `{name}` placeholders which are filled in
+/// with [`fill`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Phrase {
+  // GUI
+  ProductionName,
+  ExcelFile,
+  ExcelFileFilter,
+  OutDir,
+  ScheduleSheetNumLabel,
+  ScheduleSheetNum,
+  SceneSheetNumLabel,
+  SceneSheetNum,
+  GroupsSheetNumLabel,
+  GroupsSheetNum,
+  PeopleSheetNumLabel,
+  PeopleSheetNum,
+  VenuesSheetNumLabel,
+  VenuesSheetNum,
+  GenerateIcs,
+  ExportMasterCalendar,
+  ExportRoomCalendars,
+  ExportSceneCalendars,
+  ExportEmails,
+  ExportCalDav,
+  ExportPdf,
+  ExportHtml,
+  ExportAttendance,
+  ExportJson,
+  MailSubject,
+  MailBody,
+  MailNoCalls,
+  TimestampedOutDir,
+  ChooseExcelFile,
+  ChooseOutDir,
+  Close,
+  ColorScheme,
+  LanguageLabel,
+  ThemeLight,
+  ThemeDark,
+  // Dialogs
+  ErrorTitle,
+  SuccessTitle,
+  SuccessText,
+  NoEmailAddress,
+  OverwrittenFiles,
+  CouldNotSetExcelPath,
+  CouldNotSetOutDir,
+  CouldNotGenerate,
+  WrongScheduleSheetNum,
+  WrongSceneSheetNum,
+  WrongGroupsSheetNum,
+  WrongPeopleSheetNum,
+  WrongVenuesSheetNum,
+  // Exported events
+  RoleLabel,
+  ScenesLabel,
+  NoteLabel,
+  CastLabel,
+  SessionsLabel,
+  AllDay,
+  AddressLabel,
+  DirectionsLabel,
+  EstimatedEnd,
+  SceneLabel,
+  OthersLabel,
+  ScheduleLabel,
+  PeopleLabel,
+  RoomsLabel,
+  DateLabel,
+  TimeLabel,
+  RoomLabel,
+  CallLabel,
+  CalendarLink,
+  AttendanceSheet,
+  TotalLabel,
+  AllScenes,
+  CallScenes,
+  CallSilentPlay,
+  CallEveryone,
+  CallObserve,
+  // Errors
+  ErrorCalamine,
+  ErrorIo,
+  ErrorIced,
+  ErrorSerdeJson,
+  ErrorExcelParse,
+  ErrorExcel,
+  ErrorIcs,
+  ErrorMail,
+  ErrorServe,
+  ErrorCalDav,
+  IcsNoUtcTime,
+  MailInvalidSender,
+  MailNoSender,
+  MailInvalidAddress,
+  MailNotCreated,
+  MailNotSent,
+  CalDavRequestFailed,
+  CalDavNotConfigured,
+  ErrorXlsx,
+  ErrorModelVersion,
+  ErrorModelSilentPlay,
+  ErrorCalendarImport,
+  ErrorFileNameCollision,
+  ErrorPlanParse,
+  // Parsing errors
+  NoFirstRow,
+  NoLocationInFirstRow,
+  WrongNumberOfColumns,
+  LocationMissing,
+  DateMissing,
+  WrongDateFormat,
+  WrongTimeFormat,
+  WrongSceneName,
+  WrongRole,
+  WrongPerson,
+  WrongGroupName,
+  WrongGroupMember,
+  WrongPersonName,
+  PlanWrongSection,
+  PlanWrongSetting,
+  PlanWrongDate,
+  PlanWrongScenes,
+  PlanMissingField,
+  WrongSessionScenes,
+  WrongSessionRooms,
+  WrongSessionNote,
+  WrongRecurrence,
+  WrongEntryType,
+  EntryRehearsal,
+  EntryDressRehearsal,
+  EntryPerformance,
+  EntryMeeting,
+  WrongRoomName,
+  WrongCoordinates,
+}
+
+impl Language {
+  pub const ALL: [Language; 3] = [Language::German, Language::English, Language::French];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Language::German => "Deutsch",
+      Language::English => "English",
+      Language::French => "Français",
+    }
+  }
+
+  /// Language tag, e.g. for the `lang` attribute of web pages.
+  pub fn code(&self) -> &'static str {
+    match self {
+      Language::German => "de",
+      Language::English => "en",
+      Language::French => "fr",
+    }
+  }
+
+  pub fn tr(&self, phrase: Phrase) -> &'static str {
+    match self {
+      Language::German => german(phrase),
+      Language::English => english(phrase),
+      Language::French => french(phrase),
+    }
+  }
+}
+
+impl Display for Phrase {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", Language::English.tr(*self))
+  }
+}
+
+/// Replaces the `{name}` placeholders of a translated phrase.
+pub fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+  args
+    .iter()
+    .fold(template.to_owned(), |text, (name, value)| {
+      text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+fn german(phrase: Phrase) -> &'static str {
+  match phrase {
+    Phrase::ProductionName => "Name der Produktion",
+    Phrase::ExcelFile => "Szenenplan Excel",
+    Phrase::ExcelFileFilter => "Excel Datei",
+    Phrase::OutDir => "Ausgabeordner für ICS Dateien",
+    Phrase::ScheduleSheetNumLabel => "Arbeitsblatt Nummer für den Terminplan:",
+    Phrase::ScheduleSheetNum => "Terminplan Nummer",
+    Phrase::SceneSheetNumLabel => "Arbeitsblatt Nummer für den Einsatzplan:",
+    Phrase::SceneSheetNum => "Einsatzplan Nummer",
+    Phrase::GroupsSheetNumLabel => "Arbeitsblatt Nummer für die Gruppen:",
+    Phrase::GroupsSheetNum => "Gruppen Nummer (optional)",
+    Phrase::PeopleSheetNumLabel => "Arbeitsblatt Nummer für die Personen:",
+    Phrase::PeopleSheetNum => "Personen Nummer (optional)",
+    Phrase::VenuesSheetNumLabel => "Arbeitsblatt Nummer für die Orte:",
+    Phrase::VenuesSheetNum => "Orte Nummer (optional)",
+    Phrase::GenerateIcs => "Generiere ICS Dateien",
+    Phrase::ExportMasterCalendar => "Gesamtkalender mit allen Terminen",
+    Phrase::ExportRoomCalendars => "Ein Kalender pro Raum",
+    Phrase::ExportSceneCalendars => "Ein Kalender pro Szene",
+    Phrase::ExportEmails => "E-Mails erstellen",
+    Phrase::ExportCalDav => "Auf CalDAV-Server hochladen",
+    Phrase::ExportPdf => "PDF zum Ausdrucken",
+    Phrase::ExportHtml => "Webseite",
+    Phrase::ExportAttendance => "Anwesenheitsliste (Excel)",
+    Phrase::ExportJson => "Daten als JSON",
+    Phrase::MailSubject => "{production}: Probenplan für {person}",
+    Phrase::MailBody => {
+      "Hallo {person}\n\nIm Anhang findest du deinen Probenplan für {production}.\n\nDeine nächsten Proben:\n{calls}\n"
+    }
+    Phrase::MailNoCalls => "Keine",
+    Phrase::TimestampedOutDir => "In neuen Ordner mit Zeitstempel schreiben",
+    Phrase::ChooseExcelFile => "Wähle eine Excel Datei",
+    Phrase::ChooseOutDir => "Wähle ICS Ausgabe Ordner",
+    Phrase::Close => "Schliessen",
+    Phrase::ColorScheme => "Farbschema:",
+    Phrase::LanguageLabel => "Sprache:",
+    Phrase::ThemeLight => "Hell",
+    Phrase::ThemeDark => "Dunkel",
+    Phrase::ErrorTitle => "Fehler",
+    Phrase::SuccessTitle => "Hurra!",
+    Phrase::SuccessText => "Alle ICS Dateien wurden generiert!",
+    Phrase::NoEmailAddress => "Ohne E-Mail-Adresse, daher keine Nachricht: {people}",
+    Phrase::OverwrittenFiles => "Diese Dateien waren nicht von einem früheren Export und wurden überschrieben: {files}",
+    Phrase::CouldNotSetExcelPath => "Excel Datei konnte nicht gesetzt werden: {error}",
+    Phrase::CouldNotSetOutDir => "Ausgabeordner konnte nicht gesetzt werden: {error}",
+    Phrase::CouldNotGenerate => "ICS Dateien konnten nicht generiert werden: {error}",
+    Phrase::WrongScheduleSheetNum => {
+      "Falsche Terminplan Nummer. Muss eine positive ganze Zahl sein, ist aber: {value}"
+    }
+    Phrase::WrongSceneSheetNum => {
+      "Falsche Einsatzplan Nummer. Muss eine positive ganze Zahl sein, ist aber: {value}"
+    }
+    Phrase::WrongGroupsSheetNum => {
+      "Falsche Gruppen Nummer. Muss eine positive ganze Zahl sein, ist aber: {value}"
+    }
+    Phrase::WrongPeopleSheetNum => {
+      "Falsche Personen Nummer. Muss eine positive ganze Zahl sein, ist aber: {value}"
+    }
+    Phrase::WrongVenuesSheetNum => {
+      "Falsche Orte Nummer. Muss eine positive ganze Zahl sein, ist aber: {value}"
+    }
+    Phrase::RoleLabel => "Rolle",
+    Phrase::ScenesLabel => "Szenen",
+    Phrase::NoteLabel => "Anmerkung",
+    Phrase::CastLabel => "Besetzung",
+    Phrase::SessionsLabel => "Parallel",
+    Phrase::AllDay => "Ganztägig",
+    Phrase::AddressLabel => "Adresse",
+    Phrase::DirectionsLabel => "Wegbeschreibung",
+    Phrase::EstimatedEnd => "Ende geschätzt",
+    Phrase::SceneLabel => "Szene",
+    Phrase::OthersLabel => "Weitere",
+    Phrase::ScheduleLabel => "Probenplan",
+    Phrase::PeopleLabel => "Personen",
+    Phrase::RoomsLabel => "Räume",
+    Phrase::DateLabel => "Datum",
+    Phrase::TimeLabel => "Zeit",
+    Phrase::RoomLabel => "Raum",
+    Phrase::CallLabel => "Einsatz",
+    Phrase::CalendarLink => "Kalender herunterladen (.ics)",
+    Phrase::AttendanceSheet => "Anwesenheit",
+    Phrase::TotalLabel => "Summe",
+    Phrase::AllScenes => "Alle Szenen",
+    Phrase::CallScenes => "Szenenprobe",
+    Phrase::CallSilentPlay => "Stummes Spiel",
+    Phrase::CallEveryone => "Alle",
+    Phrase::CallObserve => "Hospitation",
+    Phrase::ErrorCalamine => "Fehler beim Lesen der Excel Datei mit calamine: {error}",
+    Phrase::ErrorIo => "Ein-/Ausgabefehler: {error}",
+    Phrase::ErrorIced => "GUI konnte nicht gestartet werden: {error}",
+    Phrase::ErrorSerdeJson => "Daten konnten nicht gelesen oder geschrieben werden: {error}",
+    Phrase::ErrorExcelParse => "Fehler in Datei '{file}' im Arbeitsblatt '{sheet}' (Zeile {row}, Spalte {column}). {expected} Unerwarteter Wert '{token}'.",
+    Phrase::ErrorExcel => "Fehler beim Lesen der Excel Datei: {file}. {message}",
+    Phrase::ErrorIcs => "Fehler beim Schreiben der ICS Dateien. {error}",
+    Phrase::ErrorMail => "Fehler beim Erstellen oder Versenden der E-Mails. {error}",
+    Phrase::ErrorServe => "Der Server konnte nicht gestartet werden. {error}",
+    Phrase::ErrorCalDav => "Fehler beim Hochladen auf den CalDAV-Server. {error}",
+    Phrase::IcsNoUtcTime => "Beginn und Ende des Termins am {value} lassen sich nicht in UTC umrechnen.",
+    Phrase::MailInvalidSender => "Ungültiger Absender '{value}': {error}",
+    Phrase::MailNoSender => "Kein Absender. Es fehlt ein Absender oder eine Produktionsleitung mit E-Mail-Adresse.",
+    Phrase::MailInvalidAddress => "Ungültige Adresse '{value}': {error}",
+    Phrase::MailNotCreated => "Die Nachricht an {value} konnte nicht erstellt werden: {error}",
+    Phrase::MailNotSent => "Die Nachrichten konnten nicht über {value} versendet werden: {error}",
+    Phrase::CalDavRequestFailed => "Die Anfrage an {url} ist fehlgeschlagen: {error}",
+    Phrase::CalDavNotConfigured => "Es ist kein CalDAV-Server eingerichtet.",
+    Phrase::ErrorXlsx => "Fehler beim Schreiben der Excel-Datei: {error}",
+    Phrase::ErrorCalendarImport => "Der Kalender '{file}' konnte nicht importiert werden. {message}",
+    Phrase::ErrorModelVersion => "Die Datei '{file}' hat das Format in Version {found}, unterstützt wird nur Version {supported}.",
+    Phrase::ErrorModelSilentPlay => "Die Rolle '{role}' in der Datei '{file}' hat nicht für jede Szene eine Angabe zum stillen Spiel.",
+    Phrase::ErrorFileNameCollision => {
+      "'{first}' und '{second}' würden beide in die Datei '{file}' geschrieben."
+    }
+    Phrase::ErrorPlanParse => "Fehler in Datei '{file}' (Zeile {line}, Spalte {column}). {expected} Unerwarteter Wert '{token}'.",
+    Phrase::NoFirstRow => {
+      "Keine erste Zeile gefunden. Sie muss mindestens die Angabe zum Ort enthalten."
+    }
+    Phrase::NoLocationInFirstRow => {
+      "Falsches Format der Excel Datei. Die erste Zeile sollte die Angabe zum Ort enthalten."
+    }
+    Phrase::WrongNumberOfColumns => "Falsches Format der Excel Datei. Es braucht 5 Spalten.",
+    Phrase::LocationMissing => "Der Ort muss angegeben werden.",
+    Phrase::DateMissing => "Das Datum muss angegeben werden.",
+    Phrase::WrongDateFormat => "Falsches Datumsformat, erwartet wird TT.MM.JJ, z.B. 01.01.22.",
+    Phrase::WrongTimeFormat => "Falsches Zeitformat, erwartet wird HH:MM, z.B. 12:00.",
+    Phrase::WrongSceneName => "Der Szenenname muss ein Text oder eine Zahl sein.",
+    Phrase::WrongRole => "Die Rolle muss ein Text sein.",
+    Phrase::WrongPerson => "Die Person, welche die Rolle spielt, muss ein Text sein.",
+    Phrase::WrongGroupName => "Der Gruppenname muss ein Text sein.",
+    Phrase::WrongGroupMember => "Die Mitglieder einer Gruppe müssen Texte sein.",
+    Phrase::WrongPersonName => "Der Name der Person muss ein Text sein.",
+    Phrase::PlanWrongSection => "Erwartet wird einer der Abschnitte [schedule], [cast], [groups] oder [venues].",
+    Phrase::PlanWrongSetting => {
+      "Erwartet wird eine Angabe 'location: …' oder 'silent play from: JJJJ-MM-TT'."
+    }
+    Phrase::PlanWrongDate => "Falsches Datumsformat, erwartet wird JJJJ-MM-TT, z.B. 2022-01-01.",
+    Phrase::PlanWrongScenes => "Erwartet wird eine Liste von Szenen, z.B. Sz 3, 5.",
+    Phrase::PlanMissingField => "Hier fehlt ein Feld.",
+    Phrase::WrongSessionScenes => {
+      "Parallele Proben brauchen je eine Liste von Szenen, getrennt durch ';'."
+    }
+    Phrase::WrongSessionRooms => "Es sind mehr Räume als parallele Proben angegeben.",
+    Phrase::WrongSessionNote => "Parallele Proben haben die Anmerkung der Zeile darüber.",
+    Phrase::WrongRecurrence => {
+      "Erwartet wird eine Wiederholung wie 05.03.24 wöchentlich bis 28.05.24 ohne 02.04.24, die Ausnahmen müssen Termine der Wiederholung sein."
+    }
+    Phrase::WrongEntryType => {
+      "Unbekannte Art des Termins, erwartet wird z.B. Probe, Hauptprobe, Vorstellung oder Besprechung."
+    }
+    Phrase::EntryRehearsal => "Probe",
+    Phrase::EntryDressRehearsal => "Hauptprobe",
+    Phrase::EntryPerformance => "Vorstellung",
+    Phrase::EntryMeeting => "Besprechung",
+    Phrase::WrongRoomName => "Der Raum muss ein Text sein.",
+    Phrase::WrongCoordinates => "Koordinaten müssen Zahlen in Grad sein, z.B. 47.37, 8.54.",
+  }
+}
+
+fn english(phrase: Phrase) -> &'static str {
+  match phrase {
+    Phrase::ProductionName => "Production name",
+    Phrase::ExcelFile => "Scene plan Excel",
+    Phrase::ExcelFileFilter => "Excel File",
+    Phrase::OutDir => "Output folder for ICS files",
+    Phrase::ScheduleSheetNumLabel => "Worksheet number of the schedule:",
+    Phrase::ScheduleSheetNum => "Schedule number",
+    Phrase::SceneSheetNumLabel => "Worksheet number of the cast plan:",
+    Phrase::SceneSheetNum => "Cast plan number",
+    Phrase::GroupsSheetNumLabel => "Worksheet number of the groups:",
+    Phrase::GroupsSheetNum => "Groups number (optional)",
+    Phrase::PeopleSheetNumLabel => "Worksheet number of the people:",
+    Phrase::PeopleSheetNum => "People number (optional)",
+    Phrase::VenuesSheetNumLabel => "Worksheet number of the venues:",
+    Phrase::VenuesSheetNum => "Venues number (optional)",
+    Phrase::GenerateIcs => "Generate ICS files",
+    Phrase::ExportMasterCalendar => "Master calendar with all entries",
+    Phrase::ExportRoomCalendars => "One calendar per room",
+    Phrase::ExportSceneCalendars => "One calendar per scene",
+    Phrase::ExportEmails => "Write e-mails",
+    Phrase::ExportCalDav => "Upload to CalDAV server",
+    Phrase::ExportPdf => "PDF for printing",
+    Phrase::ExportHtml => "Website",
+    Phrase::ExportAttendance => "Attendance matrix (Excel)",
+    Phrase::ExportJson => "Data as JSON",
+    Phrase::MailSubject => "{production}: rehearsal schedule for {person}",
+    Phrase::MailBody => {
+      "Hello {person}\n\nAttached is your rehearsal schedule for {production}.\n\nYour upcoming rehearsals:\n{calls}\n"
+    }
+    Phrase::MailNoCalls => "None",
+    Phrase::TimestampedOutDir => "Write into a new timestamped folder",
+    Phrase::ChooseExcelFile => "Choose an Excel file",
+    Phrase::ChooseOutDir => "Choose ICS output folder",
+    Phrase::Close => "Close",
+    Phrase::ColorScheme => "Color scheme:",
+    Phrase::LanguageLabel => "Language:",
+    Phrase::ThemeLight => "Light",
+    Phrase::ThemeDark => "Dark",
+    Phrase::ErrorTitle => "Error",
+    Phrase::SuccessTitle => "Hooray!",
+    Phrase::SuccessText => "All ICS files have been generated!",
+    Phrase::NoEmailAddress => "No email address, so no message for: {people}",
+    Phrase::OverwrittenFiles => "These files were not written by an earlier export and have been overwritten: {files}",
+    Phrase::CouldNotSetExcelPath => "Could not set excel file path: {error}",
+    Phrase::CouldNotSetOutDir => "Could not set out dir: {error}",
+    Phrase::CouldNotGenerate => "Could not generate ics files: {error}",
+    Phrase::WrongScheduleSheetNum => {
+      "Wrong schedule sheet number entry. Must be a positive integer but found: {value}"
+    }
+    Phrase::WrongSceneSheetNum => {
+      "Wrong scene sheet number entry. Must be a positive integer but found: {value}"
+    }
+    Phrase::WrongGroupsSheetNum => {
+      "Wrong groups sheet number entry. Must be a positive integer but found: {value}"
+    }
+    Phrase::WrongPeopleSheetNum => {
+      "Wrong people sheet number entry. Must be a positive integer but found: {value}"
+    }
+    Phrase::WrongVenuesSheetNum => {
+      "Wrong venues sheet number entry. Must be a positive integer but found: {value}"
+    }
+    Phrase::RoleLabel => "Role",
+    Phrase::ScenesLabel => "Scenes",
+    Phrase::NoteLabel => "Note",
+    Phrase::CastLabel => "Cast",
+    Phrase::SessionsLabel => "In parallel",
+    Phrase::AllDay => "All day",
+    Phrase::AddressLabel => "Address",
+    Phrase::DirectionsLabel => "Directions",
+    Phrase::EstimatedEnd => "End time estimated",
+    Phrase::SceneLabel => "Scene",
+    Phrase::OthersLabel => "Others",
+    Phrase::ScheduleLabel => "Schedule",
+    Phrase::PeopleLabel => "People",
+    Phrase::RoomsLabel => "Rooms",
+    Phrase::DateLabel => "Date",
+    Phrase::TimeLabel => "Time",
+    Phrase::RoomLabel => "Room",
+    Phrase::CallLabel => "Call",
+    Phrase::CalendarLink => "Download calendar (.ics)",
+    Phrase::AttendanceSheet => "Attendance",
+    Phrase::TotalLabel => "Total",
+    Phrase::AllScenes => "All scenes",
+    Phrase::CallScenes => "Scene rehearsal",
+    Phrase::CallSilentPlay => "Silent play",
+    Phrase::CallEveryone => "Everyone",
+    Phrase::CallObserve => "Observe",
+    Phrase::ErrorCalamine => "Error while reading the excel file with calamine: {error}",
+    Phrase::ErrorIo => "IO error: {error}",
+    Phrase::ErrorIced => "Could not run gui: {error}",
+    Phrase::ErrorSerdeJson => "Could not deserialize or serialize data: {error}",
+    Phrase::ErrorExcelParse => "Parsing error for file '{file}' in sheet '{sheet}' (row {row}, column {column}). {expected} Unexpected token '{token}'.",
+    Phrase::ErrorExcel => "Error while reading the excel file: {file}. {message}",
+    Phrase::ErrorIcs => "Error during writing of the ics files. {error}",
+    Phrase::ErrorMail => "Error while creating or sending the e-mails. {error}",
+    Phrase::ErrorServe => "Could not start the server. {error}",
+    Phrase::ErrorCalDav => "Error while uploading to the CalDAV server. {error}",
+    Phrase::IcsNoUtcTime => "Start and end of the entry on {value} could not be converted to UTC.",
+    Phrase::MailInvalidSender => "Invalid sender '{value}': {error}",
+    Phrase::MailNoSender => "No sender address. Set a sender or a production manager with an email address.",
+    Phrase::MailInvalidAddress => "Invalid address '{value}': {error}",
+    Phrase::MailNotCreated => "The message to {value} could not be created: {error}",
+    Phrase::MailNotSent => "The messages could not be sent via {value}: {error}",
+    Phrase::CalDavRequestFailed => "The request to {url} failed: {error}",
+    Phrase::CalDavNotConfigured => "No CalDAV server is configured.",
+    Phrase::ErrorXlsx => "Error while writing the excel file: {error}",
+    Phrase::ErrorCalendarImport => "Could not import the calendar '{file}'. {message}",
+    Phrase::ErrorModelVersion => "The file '{file}' has format version {found}, but only version {supported} is supported.",
+    Phrase::ErrorModelSilentPlay => "The role '{role}' in the file '{file}' does not have a silent play mark for every scene.",
+    Phrase::ErrorFileNameCollision => {
+      "'{first}' and '{second}' would both be written to the file '{file}'."
+    }
+    Phrase::ErrorPlanParse => "Parsing error for file '{file}' (line {line}, column {column}). {expected} Unexpected token '{token}'.",
+    Phrase::NoFirstRow => {
+      "No first row found. Needs to contain at least the information about the location."
+    }
+    Phrase::NoLocationInFirstRow => {
+      "Wrong Excel file format. First row should contain the information about the location."
+    }
+    Phrase::WrongNumberOfColumns => "Wrong Excel file format. There should be 5 columns.",
+    Phrase::LocationMissing => "The location should be specified.",
+    Phrase::DateMissing => "The date should be specified.",
+    Phrase::WrongDateFormat => "Wrong date string format should be DD.MM.YY., e.g. 01.01.22.",
+    Phrase::WrongTimeFormat => "Wrong time string format should be HH:MM, e.g. 12:00",
+    Phrase::WrongSceneName => "Scene name should be a string or a float.",
+    Phrase::WrongRole => "Role should be a string.",
+    Phrase::WrongPerson => "Person who plays the role should be a string.",
+    Phrase::WrongGroupName => "Group name should be a string.",
+    Phrase::WrongGroupMember => "Group members should be strings.",
+    Phrase::WrongPersonName => "Name of the person should be a string.",
+    Phrase::PlanWrongSection => "Expected one of the sections [schedule], [cast], [groups] or [venues].",
+    Phrase::PlanWrongSetting => {
+      "Expected a setting 'location: …' or 'silent play from: YYYY-MM-DD'."
+    }
+    Phrase::PlanWrongDate => "Wrong date format, should be YYYY-MM-DD, e.g. 2022-01-01.",
+    Phrase::PlanWrongScenes => "Expected a list of scenes, e.g. Sz 3, 5.",
+    Phrase::PlanMissingField => "A field is missing here.",
+    Phrase::WrongSessionScenes => "Parallel sessions need a list of scenes each, separated by ';'.",
+    Phrase::WrongSessionRooms => "There are more rooms than parallel sessions.",
+    Phrase::WrongSessionNote => "Parallel sessions share the note of the row above.",
+    Phrase::WrongRecurrence => {
+      "Expected a recurrence like 05.03.24 weekly until 28.05.24 except 02.04.24, the exceptions have to be dates of the recurrence."
+    }
+    Phrase::WrongEntryType => {
+      "Unknown type of entry, expected e.g. rehearsal, dress rehearsal, performance or meeting."
+    }
+    Phrase::EntryRehearsal => "Rehearsal",
+    Phrase::EntryDressRehearsal => "Dress rehearsal",
+    Phrase::EntryPerformance => "Performance",
+    Phrase::EntryMeeting => "Meeting",
+    Phrase::WrongRoomName => "Room should be a string.",
+    Phrase::WrongCoordinates => "Coordinates should be numbers in degrees, e.g. 47.37, 8.54.",
+  }
+}
+
+fn french(phrase: Phrase) -> &'static str {
+  match phrase {
+    Phrase::ProductionName => "Nom de la production",
+    Phrase::ExcelFile => "Plan des scènes Excel",
+    Phrase::ExcelFileFilter => "Fichier Excel",
+    Phrase::OutDir => "Dossier de sortie des fichiers ICS",
+    Phrase::ScheduleSheetNumLabel => "Numéro de la feuille du calendrier :",
+    Phrase::ScheduleSheetNum => "Numéro du calendrier",
+    Phrase::SceneSheetNumLabel => "Numéro de la feuille de distribution :",
+    Phrase::SceneSheetNum => "Numéro de la distribution",
+    Phrase::GroupsSheetNumLabel => "Numéro de la feuille des groupes :",
+    Phrase::GroupsSheetNum => "Numéro des groupes (facultatif)",
+    Phrase::PeopleSheetNumLabel => "Numéro de la feuille des personnes :",
+    Phrase::PeopleSheetNum => "Numéro des personnes (facultatif)",
+    Phrase::VenuesSheetNumLabel => "Numéro de la feuille des lieux :",
+    Phrase::VenuesSheetNum => "Numéro des lieux (facultatif)",
+    Phrase::GenerateIcs => "Générer les fichiers ICS",
+    Phrase::ExportMasterCalendar => "Calendrier général avec toutes les dates",
+    Phrase::ExportRoomCalendars => "Un calendrier par salle",
+    Phrase::ExportSceneCalendars => "Un calendrier par scène",
+    Phrase::ExportEmails => "Créer des e-mails",
+    Phrase::ExportCalDav => "Envoyer au serveur CalDAV",
+    Phrase::ExportPdf => "PDF à imprimer",
+    Phrase::ExportHtml => "Site web",
+    Phrase::ExportAttendance => "Tableau de présence (Excel)",
+    Phrase::ExportJson => "Données en JSON",
+    Phrase::MailSubject => "{production} : planning des répétitions pour {person}",
+    Phrase::MailBody => {
+      "Bonjour {person}\n\nVous trouverez ci-joint votre planning des répétitions pour {production}.\n\nVos prochaines répétitions :\n{calls}\n"
+    }
+    Phrase::MailNoCalls => "Aucune",
+    Phrase::TimestampedOutDir => "Écrire dans un nouveau dossier horodaté",
+    Phrase::ChooseExcelFile => "Choisir un fichier Excel",
+    Phrase::ChooseOutDir => "Choisir le dossier de sortie ICS",
+    Phrase::Close => "Fermer",
+    Phrase::ColorScheme => "Thème :",
+    Phrase::LanguageLabel => "Langue :",
+    Phrase::ThemeLight => "Clair",
+    Phrase::ThemeDark => "Sombre",
+    Phrase::ErrorTitle => "Erreur",
+    Phrase::SuccessTitle => "Hourra !",
+    Phrase::SuccessText => "Tous les fichiers ICS ont été générés !",
+    Phrase::NoEmailAddress => "Pas d'adresse e-mail, donc pas de message pour : {people}",
+    Phrase::OverwrittenFiles => "Ces fichiers ne provenaient pas d'un export précédent et ont été écrasés : {files}",
+    Phrase::CouldNotSetExcelPath => "Impossible de choisir le fichier Excel : {error}",
+    Phrase::CouldNotSetOutDir => "Impossible de choisir le dossier de sortie : {error}",
+    Phrase::CouldNotGenerate => "Impossible de générer les fichiers ICS : {error}",
+    Phrase::WrongScheduleSheetNum => {
+      "Numéro de feuille du calendrier invalide. Un entier positif est attendu, reçu : {value}"
+    }
+    Phrase::WrongSceneSheetNum => {
+      "Numéro de feuille de distribution invalide. Un entier positif est attendu, reçu : {value}"
+    }
+    Phrase::WrongGroupsSheetNum => {
+      "Numéro de feuille des groupes invalide. Un entier positif est attendu, reçu : {value}"
+    }
+    Phrase::WrongPeopleSheetNum => {
+      "Numéro de feuille des personnes invalide. Un entier positif est attendu, reçu : {value}"
+    }
+    Phrase::WrongVenuesSheetNum => {
+      "Numéro de feuille des lieux invalide. Un entier positif est attendu, reçu : {value}"
+    }
+    Phrase::RoleLabel => "Rôle",
+    Phrase::ScenesLabel => "Scènes",
+    Phrase::NoteLabel => "Remarque",
+    Phrase::CastLabel => "Distribution",
+    Phrase::SessionsLabel => "En parallèle",
+    Phrase::AllDay => "Toute la journée",
+    Phrase::AddressLabel => "Adresse",
+    Phrase::DirectionsLabel => "Itinéraire",
+    Phrase::EstimatedEnd => "Heure de fin estimée",
+    Phrase::SceneLabel => "Scène",
+    Phrase::OthersLabel => "Autres",
+    Phrase::ScheduleLabel => "Planning",
+    Phrase::PeopleLabel => "Personnes",
+    Phrase::RoomsLabel => "Salles",
+    Phrase::DateLabel => "Date",
+    Phrase::TimeLabel => "Heure",
+    Phrase::RoomLabel => "Salle",
+    Phrase::CallLabel => "Convocation",
+    Phrase::CalendarLink => "Télécharger le calendrier (.ics)",
+    Phrase::AttendanceSheet => "Présence",
+    Phrase::TotalLabel => "Total",
+    Phrase::AllScenes => "Toutes les scènes",
+    Phrase::CallScenes => "Répétition de scènes",
+    Phrase::CallSilentPlay => "Jeu muet",
+    Phrase::CallEveryone => "Tous",
+    Phrase::CallObserve => "Observation",
+    Phrase::ErrorCalamine => "Erreur lors de la lecture du fichier Excel avec calamine : {error}",
+    Phrase::ErrorIo => "Erreur d'entrée/sortie : {error}",
+    Phrase::ErrorIced => "Impossible de lancer l'interface : {error}",
+    Phrase::ErrorSerdeJson => "Impossible de lire ou d'écrire les données : {error}",
+    Phrase::ErrorExcelParse => "Erreur dans le fichier '{file}', feuille '{sheet}' (ligne {row}, colonne {column}). {expected} Valeur inattendue '{token}'.",
+    Phrase::ErrorExcel => "Erreur lors de la lecture du fichier Excel : {file}. {message}",
+    Phrase::ErrorIcs => "Erreur lors de l'écriture des fichiers ICS. {error}",
+    Phrase::ErrorMail => "Erreur lors de la création ou de l'envoi des e-mails. {error}",
+    Phrase::ErrorServe => "Impossible de démarrer le serveur. {error}",
+    Phrase::ErrorCalDav => "Erreur lors de l'envoi au serveur CalDAV. {error}",
+    Phrase::IcsNoUtcTime => "Le début et la fin de l'entrée du {value} ne peuvent pas être convertis en UTC.",
+    Phrase::MailInvalidSender => "Expéditeur invalide '{value}' : {error}",
+    Phrase::MailNoSender => "Aucun expéditeur. Indiquez un expéditeur ou une direction de production avec une adresse e-mail.",
+    Phrase::MailInvalidAddress => "Adresse invalide '{value}' : {error}",
+    Phrase::MailNotCreated => "Le message pour {value} n'a pas pu être créé : {error}",
+    Phrase::MailNotSent => "Les messages n'ont pas pu être envoyés via {value} : {error}",
+    Phrase::CalDavRequestFailed => "La requête vers {url} a échoué : {error}",
+    Phrase::CalDavNotConfigured => "Aucun serveur CalDAV n'est configuré.",
+    Phrase::ErrorXlsx => "Erreur lors de l'écriture du fichier Excel : {error}",
+    Phrase::ErrorCalendarImport => "Impossible d'importer le calendrier '{file}'. {message}",
+    Phrase::ErrorModelVersion => "Le fichier '{file}' a le format en version {found}, seule la version {supported} est prise en charge.",
+    Phrase::ErrorModelSilentPlay => "Le rôle '{role}' dans le fichier '{file}' n'a pas d'indication de jeu muet pour chaque scène.",
+    Phrase::ErrorFileNameCollision => {
+      "'{first}' et '{second}' seraient tous deux écrits dans le fichier '{file}'."
+    }
+    Phrase::ErrorPlanParse => "Erreur dans le fichier '{file}' (ligne {line}, colonne {column}). {expected} Valeur inattendue '{token}'.",
+    Phrase::NoFirstRow => {
+      "Aucune première ligne trouvée. Elle doit au moins contenir l'information sur le lieu."
+    }
+    Phrase::NoLocationInFirstRow => {
+      "Format Excel invalide. La première ligne doit contenir l'information sur le lieu."
+    }
+    Phrase::WrongNumberOfColumns => "Format Excel invalide. Il faut 5 colonnes.",
+    Phrase::LocationMissing => "Le lieu doit être indiqué.",
+    Phrase::DateMissing => "La date doit être indiquée.",
+    Phrase::WrongDateFormat => "Format de date invalide, attendu JJ.MM.AA, p. ex. 01.01.22.",
+    Phrase::WrongTimeFormat => "Format d'heure invalide, attendu HH:MM, p. ex. 12:00.",
+    Phrase::WrongSceneName => "Le nom de la scène doit être un texte ou un nombre.",
+    Phrase::WrongRole => "Le rôle doit être un texte.",
+    Phrase::WrongPerson => "La personne qui joue le rôle doit être un texte.",
+    Phrase::WrongGroupName => "Le nom du groupe doit être un texte.",
+    Phrase::WrongGroupMember => "Les membres d'un groupe doivent être des textes.",
+    Phrase::WrongPersonName => "Le nom de la personne doit être un texte.",
+    Phrase::PlanWrongSection => "Attendu l'une des sections [schedule], [cast], [groups] ou [venues].",
+    Phrase::PlanWrongSetting => {
+      "Attendu une indication 'location: …' ou 'silent play from: AAAA-MM-JJ'."
+    }
+    Phrase::PlanWrongDate => "Format de date invalide, attendu AAAA-MM-JJ, p. ex. 2022-01-01.",
+    Phrase::PlanWrongScenes => "Attendu une liste de scènes, p. ex. Sz 3, 5.",
+    Phrase::PlanMissingField => "Il manque un champ ici.",
+    Phrase::WrongSessionScenes => {
+      "Les répétitions parallèles ont besoin chacune d'une liste de scènes, séparées par ';'."
+    }
+    Phrase::WrongSessionRooms => "Il y a plus de salles que de répétitions parallèles.",
+    Phrase::WrongSessionNote => "Les répétitions parallèles partagent la remarque de la ligne au-dessus.",
+    Phrase::WrongRecurrence => {
+      "Attendu une répétition comme 05.03.24 hebdomadaire jusqu'au 28.05.24 sauf 02.04.24, les exceptions doivent être des dates de la répétition."
+    }
+    Phrase::WrongEntryType => {
+      "Type de rendez-vous inconnu, attendu p. ex. répétition, générale, représentation ou réunion."
+    }
+    Phrase::EntryRehearsal => "Répétition",
+    Phrase::EntryDressRehearsal => "Répétition générale",
+    Phrase::EntryPerformance => "Représentation",
+    Phrase::EntryMeeting => "Réunion",
+    Phrase::WrongRoomName => "La salle doit être un texte.",
+    Phrase::WrongCoordinates => "Les coordonnées doivent être des nombres en degrés, p. ex. 47.37, 8.54.",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::structures::SceneSchedulerError;
+
+  #[test]
+  fn test_fill_replaces_named_placeholders() {
+    assert_eq!(
+      fill(
+        Language::English.tr(Phrase::WrongSceneSheetNum),
+        &[("value", &"-1")]
+      ),
+      "Wrong scene sheet number entry. Must be a positive integer but found: -1"
+    );
+  }
+
+  #[test]
+  fn test_phrase_display_is_english() {
+    assert_eq!(
+      Phrase::DateMissing.to_string(),
+      "The date should be specified."
+    );
+  }
+
+  #[test]
+  fn test_errors_are_localized() {
+    let error = SceneSchedulerError::Mail {
+      message: Phrase::MailInvalidAddress,
+      value: "anna@".to_string(),
+      error: "missing domain".to_string(),
+    };
+    assert_eq!(
+      error.to_string(),
+      "Error while creating or sending the e-mails. Invalid address 'anna@': missing domain"
+    );
+    assert_eq!(
+      error.localized(Language::German),
+      "Fehler beim Erstellen oder Versenden der E-Mails. Ungültige Adresse 'anna@': missing domain"
+    );
+    let error = SceneSchedulerError::CalDav {
+      message: Phrase::CalDavNotConfigured,
+      url: String::new(),
+      error: String::new(),
+    };
+    assert_eq!(
+      error.localized(Language::French),
+      "Erreur lors de l'envoi au serveur CalDAV. Aucun serveur CalDAV n'est configuré."
+    );
+  }
+}
@@ -1,87 +1,548 @@
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::structures::{PersonToSceneAndScheduleEntry, SceneSchedulerError, Scenes};
+use crate::config::{Config, MASTER_CALENDAR_NAME, ROOM_CALENDAR_DIR, SCENE_CALENDAR_DIR};
+use crate::i18n::{Language, Phrase};
+use crate::output::OutputFiles;
+use crate::sorting::{filter_by_scenes, find_person_info, find_venue, get_called_persons};
+use crate::structures::{
+  CallType, EntryCategory, Person, PersonInfo, PersonToSceneAndScheduleEntry, Room, Scene,
+  SceneEntry, SceneSchedulerError, Scenes, ScheduleEntry, ScheduleEntryToCast, Venue,
+};
+use crate::template::{format_sessions, render, EventContext, EventTemplates};
 use chrono::{DateTime, Duration, NaiveDateTime, TimeZone};
 use chrono_tz::{Europe::Zurich, Tz};
-use ics::properties::{Description, DtEnd, DtStart, Location, Status, Summary};
-use ics::{escape_text, Event, ICalendar};
+use ics::parameters::{PartStat, Role, Value, CN, RSVP};
+use ics::properties::{
+  Attendee, Categories, Color, Description, DtEnd, DtStart, Geo, Location, Organizer, Status,
+  Summary, Trigger,
+};
+use ics::{escape_text, Alarm, Event, ICalendar};
 
 const ICAL_STR_FORMAT: &str = "%Y%m%dT%H%M%SZ";
-const DEFAULT_EVENT_DURATION_HOURS: i64 = 4;
+const ICAL_DATE_FORMAT: &str = "%Y%m%d";
+const PRODID: &str = "-//Fungiking//NONSGML Scene Scheduler//DE";
+
+/// Organizer and attendees of the events and the venues they take place in.
+/// Only people with a known email address can be added.
+pub struct Participants<'a> {
+  people: &'a [PersonInfo],
+  organizer: Option<&'a PersonInfo>,
+  venues: &'a [Venue],
+  called_persons: HashMap<md5::Digest, Vec<(Person, CallType)>>,
+}
+
+impl<'a> Participants<'a> {
+  pub fn new(
+    person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+    people: &'a [PersonInfo],
+    venues: &'a [Venue],
+    config: &Config,
+  ) -> Self {
+    Self {
+      people,
+      venues,
+      organizer: config
+        .production_manager
+        .as_deref()
+        .and_then(|name| find_person_info(name, people)),
+      called_persons: get_called_persons(person_to_scene_and_schedule_entry),
+    }
+  }
+
+  /// The rooms with their directory entries, if there are any.
+  pub fn venues<'r>(&self, rooms: Vec<&'r str>) -> Vec<(&'r str, Option<&'a Venue>)> {
+    rooms
+      .into_iter()
+      .map(|room| (room, find_venue(room, self.venues)))
+      .collect()
+  }
+
+  fn add_to(&self, event: &mut Event<'static>, schedule_entry: &ScheduleEntry) {
+    let organizer = self
+      .organizer
+      .and_then(|info| info.email.as_ref().map(|email| (&info.name, email)));
+    if let Some((name, email)) = organizer {
+      let mut organizer = Organizer::new(format!("mailto:{}", email));
+      organizer.add(CN::new(name.clone()));
+      event.push(organizer);
+    }
+    let called_persons = match self.called_persons.get(&schedule_entry.uuid) {
+      Some(called_persons) => called_persons,
+      None => return,
+    };
+    for (person, call_type) in called_persons {
+      let email = match find_person_info(person, self.people).and_then(|info| info.email.as_ref()) {
+        Some(email) => email,
+        None => continue,
+      };
+      let mut attendee = Attendee::new(format!("mailto:{}", email));
+      attendee.add(CN::new(person.clone()));
+      attendee.add(match call_type {
+        CallType::Observe => Role::OPT_PARTICIPANT,
+        _ => Role::REQ_PARTICIPANT,
+      });
+      attendee.add(PartStat::NEEDS_ACTION);
+      if organizer.is_some() {
+        attendee.add(RSVP::True);
+      }
+      event.push(attendee);
+    }
+  }
+}
 
 pub fn write_ics_file(
   person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
-  out_dir: &str,
+  participants: &Participants,
+  output_files: &mut OutputFiles,
+  config: &Config,
   default_location: &str,
+  language: Language,
 ) -> Result<(), SceneSchedulerError> {
-  if person_to_scene_and_schedule_entry.is_empty() {
-    return Ok(());
+  for (person, schedule_to_scene_entries) in person_to_scene_and_schedule_entry {
+    let calendar = create_person_calendar(
+      person,
+      schedule_to_scene_entries,
+      participants,
+      config,
+      default_location,
+      language,
+    )?;
+    calendar.save_file(output_files.person_file(person)?)?;
   }
+  Ok(())
+}
 
-  std::fs::create_dir_all(out_dir)?;
+/// Creates the calendar with all calls of one person.
+pub fn create_person_calendar(
+  person: &Person,
+  schedule_to_scene_entries: &[&(&ScheduleEntry, Option<&SceneEntry>)],
+  participants: &Participants,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<ICalendar<'static>, SceneSchedulerError> {
+  let mut calendar = ICalendar::new("2.0", PRODID);
+  for (_, event) in create_person_events(
+    person,
+    schedule_to_scene_entries,
+    participants,
+    config,
+    default_location,
+    language,
+  )? {
+    calendar.add_event(event);
+  }
+  Ok(calendar)
+}
 
-  for (person, schedule_to_scene_entries) in person_to_scene_and_schedule_entry {
-    // dtsart.format("%Y%m%dT%H%M%SZ").to_string()
-    let mut calendar = ICalendar::new("2.0", "-//Fungiking//NONSGML Scene Scheduler//DE");
-    for (schedule_entry, scene_entry) in schedule_to_scene_entries {
-      let start_end_date_time_naive = schedule_entry.start_stop_date_time();
-      let (start_date_time_str, stop_date_time_str) =
-        get_start_and_end_time_utc(&start_end_date_time_naive).ok_or_else(|| {
-          SceneSchedulerError::Ics(format!(
-            "Could not convert start and end time to UTC for schedule entry: {:?}",
-            schedule_entry
-          ))
-        })?;
-
-      // create event which contains the information regarding the conference
-      // add properties
-      let mut event = Event::new(
-        format!("{:x}", schedule_entry.uuid),
-        chrono::Utc::now().format(ICAL_STR_FORMAT).to_string(),
-      );
-      event.push(DtStart::new(start_date_time_str));
-      event.push(DtEnd::new(stop_date_time_str));
-      event.push(Status::confirmed());
-      event.push(Summary::new("Theater"));
-      if let Some(location) = &schedule_entry.room {
-        event.push(Location::new(location));
-      } else {
-        event.push(Location::new(default_location));
-      }
-      // Values that are "TEXT" must be escaped (only if the text contains a comma,
-      // semicolon, backslash or newline).
-      let mut description = String::new();
-      if let Some(scene_entry) = scene_entry {
-        description.push_str(format!("Rolle: {}\n", scene_entry.role).as_str());
-      };
-      match &schedule_entry.scenes {
-        Scenes::Normal(scenes) => {
-          if scenes.is_empty() {
-            description.push_str("Szenen: Alle Szenen\n");
-          } else {
-            description.push_str(format!("Szenen: {}\n", scenes.join(", ")).as_str());
-          };
-        }
-        Scenes::Special(scene) => description.push_str(format!("{}\n", scene).as_str()),
-      }
-      if let Some(note) = &schedule_entry.note {
-        description.push_str(format!("Anmerkung: {}\n", note).as_str());
-      };
-      event.push(Description::new(escape_text(description)));
-      // add event to calendar
-      calendar.add_event(event);
+/// Creates the events of one person together with their UIDs.
+pub fn create_person_events(
+  person: &Person,
+  schedule_to_scene_entries: &[&(&ScheduleEntry, Option<&SceneEntry>)],
+  participants: &Participants,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<Vec<(String, Event<'static>)>, SceneSchedulerError> {
+  let templates = config.event_templates(language);
+  let person_info = find_person_info(person, participants.people);
+  let mut events = vec![];
+  for (schedule_entry, scene_entry) in schedule_to_scene_entries {
+    let location = schedule_entry
+      .room_for(*scene_entry)
+      .unwrap_or(default_location);
+    let rooms = participants.venues(vec![location]);
+    let context = EventContext {
+      person: person.clone(),
+      role: scene_entry
+        .map(|scene_entry| scene_entry.role.clone())
+        .unwrap_or_default(),
+      call: schedule_entry
+        .call_type_for(*scene_entry, person)
+        .label(language)
+        .to_owned(),
+      email: person_info
+        .and_then(|info| info.email.clone())
+        .unwrap_or_default(),
+      phone: person_info
+        .and_then(|info| info.phone.clone())
+        .unwrap_or_default(),
+      ..event_context(
+        schedule_entry,
+        *scene_entry,
+        location,
+        &rooms,
+        default_location,
+        config,
+        language,
+      )
+    };
+    let mut event = create_event(
+      schedule_entry,
+      &rooms,
+      config.entry_category(schedule_entry.entry_type),
+      Duration::minutes(config.default_duration(schedule_entry.entry_type).into()),
+      &context,
+      &templates,
+    )?;
+    participants.add_to(&mut event, schedule_entry);
+    events.push((event_uid(schedule_entry), event));
+  }
+  Ok(events)
+}
+
+/// Single event wrapped into a calendar, as stored on a CalDAV server.
+pub fn single_event_calendar(event: Event<'static>) -> ICalendar<'static> {
+  let mut calendar = ICalendar::new("2.0", PRODID);
+  calendar.add_event(event);
+  calendar
+}
+
+/// Writes one calendar containing every schedule entry together with the
+/// whole cast which is called.
+pub fn write_master_ics_file(
+  schedule_entry_to_cast: &ScheduleEntryToCast,
+  participants: &Participants,
+  output_files: &mut OutputFiles,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<(), SceneSchedulerError> {
+  let calendar = create_master_calendar(
+    schedule_entry_to_cast,
+    participants,
+    config,
+    default_location,
+    language,
+  )?;
+  calendar.save_file(output_files.file(None, MASTER_CALENDAR_NAME, "ics")?)?;
+  Ok(())
+}
+
+pub fn create_master_calendar(
+  schedule_entry_to_cast: &ScheduleEntryToCast,
+  participants: &Participants,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<ICalendar<'static>, SceneSchedulerError> {
+  create_cast_calendar(
+    schedule_entry_to_cast.iter(),
+    None,
+    participants,
+    config,
+    default_location,
+    language,
+  )
+}
+
+/// Writes one calendar per room. Entries without a room are put into the
+/// calendar of the default location.
+pub fn write_room_ics_files(
+  schedule_entry_to_cast: &ScheduleEntryToCast,
+  participants: &Participants,
+  output_files: &mut OutputFiles,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<(), SceneSchedulerError> {
+  let calendars = create_room_calendars(
+    schedule_entry_to_cast,
+    participants,
+    config,
+    default_location,
+    language,
+  )?;
+  for (room, calendar) in calendars {
+    calendar.save_file(output_files.file(Some(ROOM_CALENDAR_DIR), &room, "ics")?)?;
+  }
+  Ok(())
+}
+
+/// Creates one calendar per room, sorted by the name of the room.
+pub fn create_room_calendars(
+  schedule_entry_to_cast: &ScheduleEntryToCast,
+  participants: &Participants,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<Vec<(Room, ICalendar<'static>)>, SceneSchedulerError> {
+  let mut room_to_entries: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+  for entry in schedule_entry_to_cast {
+    for room in entry.0.rooms(default_location) {
+      room_to_entries.entry(room).or_default().push(entry);
     }
+  }
+  room_to_entries
+    .into_iter()
+    .map(|(room, entries)| {
+      let calendar = create_cast_calendar(
+        entries.into_iter(),
+        Some(room),
+        participants,
+        config,
+        default_location,
+        language,
+      )?;
+      Ok((room.to_owned(), calendar))
+    })
+    .collect()
+}
 
-    // write calendar to file
-    let mut out_file_path = Path::new(out_dir).join(person);
-    out_file_path.set_extension("ics");
-    calendar.save_file(out_file_path)?;
+/// Writes one calendar per scene and one per configured scene group.
+pub fn write_scene_ics_files(
+  schedule_entry_to_cast: &ScheduleEntryToCast,
+  all_scenes: &[Scene],
+  participants: &Participants,
+  output_files: &mut OutputFiles,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<(), SceneSchedulerError> {
+  for (name, scenes) in scene_selections(all_scenes, config) {
+    let entries = filter_by_scenes(schedule_entry_to_cast, scenes);
+    let calendar = create_cast_calendar(
+      entries.iter(),
+      None,
+      participants,
+      config,
+      default_location,
+      language,
+    )?;
+    calendar.save_file(output_files.file(Some(SCENE_CALENDAR_DIR), name, "ics")?)?;
   }
   Ok(())
 }
 
+/// Every scene on its own followed by the configured scene groups, each with
+/// the scenes it contains.
+pub fn scene_selections<'a>(
+  all_scenes: &'a [Scene],
+  config: &'a Config,
+) -> Vec<(&'a str, &'a [Scene])> {
+  all_scenes
+    .iter()
+    .map(|scene| (scene.as_str(), std::slice::from_ref(scene)))
+    .chain(
+      config
+        .scene_groups
+        .iter()
+        .map(|group| (group.name.as_str(), group.scenes.as_slice())),
+    )
+    .collect()
+}
+
+/// Creates a calendar with the given entries and their whole cast. The
+/// calendar of a room only shows that room as location, otherwise the rooms
+/// of all sessions are listed.
+pub fn create_cast_calendar<'a>(
+  entries: impl Iterator<Item = &'a (&'a ScheduleEntry, Vec<&'a SceneEntry>)>,
+  room: Option<&str>,
+  participants: &Participants,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<ICalendar<'static>, SceneSchedulerError> {
+  let templates = config.event_templates(language);
+  let mut calendar = ICalendar::new("2.0", PRODID);
+  for (schedule_entry, cast) in entries {
+    let rooms = participants.venues(match room {
+      Some(room) => vec![room],
+      None => schedule_entry.rooms(default_location),
+    });
+    let location = rooms
+      .iter()
+      .map(|(room, _)| *room)
+      .collect::<Vec<_>>()
+      .join(", ");
+    let context = EventContext {
+      cast: format_cast(schedule_entry, cast),
+      call: schedule_entry.call_type(None).label(language).to_owned(),
+      ..event_context(
+        schedule_entry,
+        None,
+        &location,
+        &rooms,
+        default_location,
+        config,
+        language,
+      )
+    };
+    let mut event = create_event(
+      schedule_entry,
+      &rooms,
+      config.entry_category(schedule_entry.entry_type),
+      Duration::minutes(config.default_duration(schedule_entry.entry_type).into()),
+      &context,
+      &templates,
+    )?;
+    participants.add_to(&mut event, schedule_entry);
+    calendar.add_event(event);
+  }
+  Ok(calendar)
+}
+
+fn format_cast(schedule_entry: &ScheduleEntry, cast: &[&SceneEntry]) -> String {
+  cast
+    .iter()
+    .map(|scene_entry| {
+      let persons = scene_entry
+        .called_cast(schedule_entry.date)
+        .iter()
+        .map(|person| person.as_str())
+        .collect::<Vec<_>>();
+      format!("{} ({})", persons.join(" / "), scene_entry.role)
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Fills in the values which only depend on the schedule entry. With a role
+/// of a parallel session, only the scenes of that session are listed.
+fn event_context(
+  schedule_entry: &ScheduleEntry,
+  scene_entry: Option<&SceneEntry>,
+  location: &str,
+  rooms: &[(&str, Option<&Venue>)],
+  default_location: &str,
+  config: &Config,
+  language: Language,
+) -> EventContext {
+  EventContext {
+    production: config.production_name.clone(),
+    scenes: match (
+      schedule_entry.session_for(scene_entry),
+      &schedule_entry.scenes,
+    ) {
+      (Some(session), _) => session.scenes.join(", "),
+      (None, Scenes::Normal(scenes)) if scenes.is_empty() => {
+        language.tr(Phrase::AllScenes).to_owned()
+      }
+      (None, Scenes::Normal(scenes)) => scenes.join(", "),
+      (None, Scenes::Special(_)) => String::new(),
+    },
+    special: match (
+      schedule_entry.session_for(scene_entry),
+      &schedule_entry.scenes,
+    ) {
+      (None, Scenes::Special(scene)) => scene.clone(),
+      _ => String::new(),
+    },
+    room: location.to_owned(),
+    entry_type: schedule_entry.entry_type.label(language).to_owned(),
+    sessions: format_sessions(schedule_entry, default_location),
+    directions: rooms
+      .iter()
+      .filter_map(|(_, venue)| venue.and_then(|venue| venue.directions.as_deref()))
+      .collect::<Vec<_>>()
+      .join("; "),
+    estimated_end: if schedule_entry.is_all_day()
+      || (schedule_entry.start_stop_time.1.is_some() && !schedule_entry.stop_time_inferred)
+    {
+      String::new()
+    } else {
+      language.tr(Phrase::EstimatedEnd).to_owned()
+    },
+    note: schedule_entry.note.clone().unwrap_or_default(),
+    ..Default::default()
+  }
+}
+
+/// The location of every room is completed with the address of its venue,
+/// the first venue with coordinates provides them. The category of the type
+/// adds its summary, color and reminders. Entries without an end last the
+/// default duration.
+fn create_event(
+  schedule_entry: &ScheduleEntry,
+  rooms: &[(&str, Option<&Venue>)],
+  category: Option<&EntryCategory>,
+  default_duration: Duration,
+  context: &EventContext,
+  templates: &EventTemplates,
+) -> Result<Event<'static>, SceneSchedulerError> {
+  let mut event = Event::new(
+    event_uid(schedule_entry),
+    chrono::Utc::now().format(ICAL_STR_FORMAT).to_string(),
+  );
+  if schedule_entry.is_all_day() {
+    // The end of dates is exclusive, so it is the day after the last one.
+    let (start, stop) = schedule_entry.start_stop_date_time();
+    let mut dt_start = DtStart::new(start.format(ICAL_DATE_FORMAT).to_string());
+    dt_start.add(Value::DATE);
+    event.push(dt_start);
+    if let Some(stop) = stop {
+      let mut dt_end = DtEnd::new(stop.format(ICAL_DATE_FORMAT).to_string());
+      dt_end.add(Value::DATE);
+      event.push(dt_end);
+    }
+  } else {
+    let start_end_date_time_naive = schedule_entry.start_stop_date_time();
+    let (start_date_time_str, stop_date_time_str) =
+      get_start_and_end_time_utc(&start_end_date_time_naive, default_duration).ok_or_else(
+        || SceneSchedulerError::Ics {
+          message: Phrase::IcsNoUtcTime,
+          value: start_end_date_time_naive
+            .0
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        },
+      )?;
+    event.push(DtStart::new(start_date_time_str));
+    event.push(DtEnd::new(stop_date_time_str));
+  }
+  event.push(Status::confirmed());
+  let location = rooms
+    .iter()
+    .map(|(room, venue)| venue.map_or_else(|| (*room).to_owned(), Venue::location))
+    .collect::<Vec<_>>()
+    .join("; ");
+  event.push(Location::new(escape_text(location)));
+  if let Some((latitude, longitude)) = rooms
+    .iter()
+    .find_map(|(_, venue)| venue.and_then(|venue| venue.coordinates))
+  {
+    event.push(Geo::new(format!("{};{}", latitude, longitude)));
+  }
+  // Values that are "TEXT" must be escaped (only if the text contains a comma,
+  // semicolon, backslash or newline).
+  let summary = render(
+    category
+      .and_then(|category| category.summary.as_deref())
+      .unwrap_or(&templates.summary),
+    context,
+  );
+  event.push(Summary::new(escape_text(summary.clone())));
+  event.push(Description::new(escape_text(render(
+    &templates.description,
+    context,
+  ))));
+  if let Some(category) = category {
+    event.push(Categories::new(escape_text(
+      category
+        .category
+        .clone()
+        .unwrap_or_else(|| context.entry_type.clone()),
+    )));
+    if let Some(color) = &category.color {
+      event.push(Color::new(color.clone()));
+    }
+    for minutes in &category.reminders {
+      event.add_alarm(Alarm::display(
+        Trigger::new(format!("-PT{}M", minutes)),
+        Description::new(escape_text(summary.clone())),
+      ));
+    }
+  }
+  Ok(event)
+}
+
+fn event_uid(schedule_entry: &ScheduleEntry) -> String {
+  format!("{:x}", schedule_entry.uuid)
+}
+
 fn get_start_and_end_time_utc(
   start_end_date_time: &(NaiveDateTime, Option<NaiveDateTime>),
+  default_duration: Duration,
 ) -> Option<(String, String)> {
   let (start_date_time_naive, stop_date_time_opt) = start_end_date_time;
 
@@ -89,7 +550,7 @@ fn get_start_and_end_time_utc(
   let stop_date = if let Some(stop_date_time_naive) = stop_date_time_opt {
     naive_to_date_time(stop_date_time_naive)?
   } else {
-    start_date + Duration::hours(DEFAULT_EVENT_DURATION_HOURS)
+    start_date + default_duration
   };
   Some((
     start_date.naive_utc().format(ICAL_STR_FORMAT).to_string(),
@@ -104,3 +565,101 @@ fn naive_to_date_time(naive_date_time: &NaiveDateTime) -> Option<DateTime<Tz>> {
     _ => None,
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sorting::{
+    apply_entry_categories, get_schedule_entry_to_cast, get_schedule_to_scene_entry,
+  };
+  use crate::structures::{Called, EntryCategory, EntryType, SceneEntry, Scenes, Session};
+  use chrono::{NaiveDate, NaiveTime};
+
+  #[test]
+  fn test_cast_calendar_locates_every_room() {
+    let session = |scene: &str, room: &str| Session {
+      scenes: vec![scene.to_string()],
+      room: Some(room.to_string()),
+    };
+    let schedule_entry = ScheduleEntry::with_sessions(
+      NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      vec![session("1", "Saal"), session("2", "Raum B")],
+      None,
+    );
+    let venue = |room: &str, address: &str, coordinates| Venue {
+      room: room.to_string(),
+      address: Some(address.to_string()),
+      building: None,
+      floor: None,
+      directions: None,
+      coordinates,
+    };
+    let venues = vec![
+      venue("Saal", "Hauptstrasse 1", None),
+      venue("Raum B", "Nebenstrasse 2", Some((47.37, 8.54))),
+    ];
+    let config = Config::default();
+    let participants = Participants::new(&vec![], &[], &venues, &config);
+    let entries = [(&schedule_entry, vec![])];
+    let calendar = |room| {
+      create_cast_calendar(
+        entries.iter(),
+        room,
+        &participants,
+        &config,
+        "Theater",
+        Language::German,
+      )
+      .unwrap()
+      .to_string()
+      .replace("\r\n ", "")
+    };
+    let master = calendar(None);
+    assert!(master.contains(r"LOCATION:Saal\, Hauptstrasse 1\; Raum B\, Nebenstrasse 2"));
+    assert!(master.contains("GEO:47.37;8.54"));
+    let room = calendar(Some("Saal"));
+    assert!(room.contains("LOCATION:Saal\\, Hauptstrasse 1\r\n"));
+    assert!(!room.contains("GEO:"));
+  }
+
+  #[test]
+  fn test_master_calendar_contains_crew_only_entries() {
+    let mut schedule_entries = vec![ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      Scenes::Special("Technik".to_string()),
+      None,
+      None,
+    )
+    .with_type(EntryType::Meeting)];
+    let mut entry_categories = EntryCategory::defaults();
+    for category in &mut entry_categories {
+      category.called = Called::Crew;
+    }
+    apply_entry_categories(&mut schedule_entries, &entry_categories);
+    let scene_entries = vec![SceneEntry::new(
+      "Gretchen".to_string(),
+      "Anna".to_string(),
+      vec!["1".to_string()],
+      vec![false],
+    )];
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let schedule_entry_to_cast =
+      get_schedule_entry_to_cast(&schedule_to_scene_entries, &scene_entries);
+    let config = Config::default();
+    let participants = Participants::new(&vec![], &[], &[], &config);
+    let master = create_cast_calendar(
+      schedule_entry_to_cast.iter(),
+      None,
+      &participants,
+      &config,
+      "Theater",
+      Language::German,
+    )
+    .unwrap()
+    .to_string();
+    assert!(master.contains("Technik"));
+    assert!(!master.contains("Anna"));
+  }
+}
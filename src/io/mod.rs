@@ -1,4 +1,28 @@
 pub mod parsing;
+pub mod plan;
+
+pub mod calendar {
+  use std::io::BufReader;
+
+  use ical::parser::ical::component::IcalEvent;
+  use ical::IcalParser;
+
+  use crate::structures::SceneSchedulerError;
+
+  /// All events of all calendars in the file.
+  pub fn read_calendar(path: &str) -> Result<Vec<IcalEvent>, SceneSchedulerError> {
+    let file = std::fs::File::open(path)?;
+    let mut events = vec![];
+    for calendar in IcalParser::new(BufReader::new(file)) {
+      let calendar = calendar.map_err(|e| SceneSchedulerError::CalendarImport {
+        file: path.to_owned(),
+        message: e.to_string(),
+      })?;
+      events.extend(calendar.events);
+    }
+    Ok(events)
+  }
+}
 
 pub mod excel {
   use std::io::Error;
@@ -1,6 +1,10 @@
 use crate::config::{SCENE_MARK, SILENT_PLAY_MARK};
-use crate::structures::{Note, Room, SceneSchedulerError, Scenes, ScheduleEntry};
-use chrono::{NaiveDate, NaiveTime};
+use crate::i18n::Phrase;
+use crate::sorting::find_entry_type;
+use crate::structures::{
+  EntryCategory, EntryType, Note, Room, SceneSchedulerError, Scenes, ScheduleEntry, Session,
+};
+use chrono::{Duration, NaiveDate, NaiveTime};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -19,6 +23,70 @@ fn parse_scenes(scenes: &str) -> Scenes {
   }
 }
 
+/// Separates parallel sessions in the scenes and the room column, e.g.
+/// `3, 4; 7` in `Saal; Raum B`.
+const SESSION_SEPARATOR: char = ';';
+
+/// The parallel sessions if there is more than one. Every session needs a
+/// list of scenes, a missing room means the default location.
+fn parse_sessions(scenes: &str, rooms: &str) -> Result<Option<Vec<Session>>, Phrase> {
+  if !scenes.contains(SESSION_SEPARATOR) && !rooms.contains(SESSION_SEPARATOR) {
+    return Ok(None);
+  }
+  let rooms = rooms.split(SESSION_SEPARATOR).collect::<Vec<_>>();
+  let scenes = scenes.split(SESSION_SEPARATOR).collect::<Vec<_>>();
+  if rooms.len() > scenes.len() {
+    return Err(Phrase::WrongSessionRooms);
+  }
+  scenes
+    .iter()
+    .enumerate()
+    .map(|(i, scenes)| match parse_scenes(scenes) {
+      Scenes::Normal(scenes) if !scenes.is_empty() => Ok(Session {
+        scenes,
+        room: rooms
+          .get(i)
+          .map(|room| parse_room(room))
+          .filter(|room| !room.is_empty()),
+      }),
+      _ => Err(Phrase::WrongSessionScenes),
+    })
+    .collect::<Result<Vec<_>, _>>()
+    .map(Some)
+}
+
+/// The sessions of an entry to which another session is added, `None` if
+/// the entry calls everyone and cannot be split.
+pub(crate) fn sessions_of(schedule_entry: &ScheduleEntry) -> Option<Vec<Session>> {
+  if !schedule_entry.sessions.is_empty() {
+    return Some(schedule_entry.sessions.clone());
+  }
+  match &schedule_entry.scenes {
+    Scenes::Normal(scenes) if !scenes.is_empty() => Some(vec![Session {
+      scenes: scenes.clone(),
+      room: schedule_entry.room.clone(),
+    }]),
+    _ => None,
+  }
+}
+
+/// The type named in the type column, or the one recognized from the name
+/// of a special entry like "Hauptprobe". Unknown special entries are
+/// rehearsals.
+pub(crate) fn parse_entry_type(
+  scenes: &Scenes,
+  type_name: Option<&str>,
+  entry_categories: &[EntryCategory],
+) -> Result<EntryType, Phrase> {
+  match (type_name.map(str::trim), scenes) {
+    (Some(name), _) if !name.is_empty() => {
+      find_entry_type(name, entry_categories).ok_or(Phrase::WrongEntryType)
+    }
+    (_, Scenes::Special(name)) => Ok(find_entry_type(name, entry_categories).unwrap_or_default()),
+    (_, Scenes::Normal(_)) => Ok(EntryType::Rehearsal),
+  }
+}
+
 fn parse_note(note: &str) -> Note {
   note.trim().to_owned()
 }
@@ -37,7 +105,112 @@ fn parse_date(date: &str) -> Option<NaiveDate> {
   NaiveDate::parse_from_str(date_str.trim(), "%_d.%_m.%y").ok()
 }
 
-fn parse_time(time: &String) -> Option<(NaiveTime, Option<NaiveTime>)> {
+/// Like `parse_date` but also accepts dates like `2024-05-28`, as written in
+/// the plan.
+fn parse_recurrence_date(date: &str) -> Option<NaiveDate> {
+  parse_date(date).or_else(|| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok())
+}
+
+lazy_static! {
+  static ref DATE_RANGE_REGEX: Regex =
+    Regex::new(r"(\d\d?)\.(?:(\d\d?)\.(\d\d)?)?\s*[-–]\s*(?:[^\d\s]+\s*)?(\d\d?\.\d\d?\.\d\d)")
+      .expect("Wrong static regex");
+}
+/// Several days like `12.–14.04.24`, `12.04.–14.04.24` or `12.04.24-14.04.24`.
+/// Month and year of the first day default to the ones of the last day.
+fn parse_date_range(dates: &str) -> Option<(NaiveDate, NaiveDate)> {
+  let captures = DATE_RANGE_REGEX.captures(dates)?;
+  let last = parse_date(&captures[4])?;
+  let month = captures.get(2).map_or_else(
+    || last.format("%m").to_string(),
+    |month| month.as_str().to_owned(),
+  );
+  let year = captures.get(3).map_or_else(
+    || last.format("%y").to_string(),
+    |year| year.as_str().to_owned(),
+  );
+  let first = parse_date(&format!("{}.{}.{}", &captures[1], month, year))?;
+  if first <= last {
+    Some((first, last))
+  } else {
+    None
+  }
+}
+
+lazy_static! {
+  static ref RECURRENCE_REGEX: Regex = Regex::new(concat!(
+    r"(?i)\b(?:wöchentlich|weekly|hebdomadaire",
+    r"|(?:alle|every|toutes les)\s+(?P<weeks>\d+)\s+(?:wochen|weeks|semaines))",
+    r"\s+(?:bis|until|jusqu'au)\s+(?P<until>\S+)",
+    r"(?:\s+(?:ohne|außer|except|sauf)\s+(?P<except>.+?))?\s*$",
+  ))
+  .expect("Wrong static regex");
+}
+
+/// An entry repeated every week or every few weeks, e.g.
+/// `wöchentlich bis 28.05.24 ohne 02.04.24, 16.04.24` or
+/// `every 2 weeks until 2024-05-28`.
+#[derive(Debug)]
+pub(crate) struct Recurrence<'a> {
+  weeks: Option<&'a str>,
+  until: &'a str,
+  except: Option<&'a str>,
+}
+
+/// Splits `05.03.24 wöchentlich bis 28.05.24` into the text before the
+/// recurrence and the recurrence, `None` if the entry does not recur.
+pub(crate) fn split_recurrence(text: &str) -> Option<(&str, Recurrence<'_>)> {
+  let captures = RECURRENCE_REGEX.captures(text)?;
+  let recurrence = Recurrence {
+    weeks: captures.name("weeks").map(|weeks| weeks.as_str()),
+    until: captures.name("until")?.as_str(),
+    except: captures.name("except").map(|except| except.as_str()),
+  };
+  Some((&text[..captures.get(0)?.start()], recurrence))
+}
+
+impl Recurrence<'_> {
+  /// The days from `first` up to the last one without the exceptions, which
+  /// have to be days of the recurrence. `parse_date` reads the dates in the
+  /// format of the input.
+  pub(crate) fn dates(
+    &self,
+    first: NaiveDate,
+    parse_date: impl Fn(&str) -> Option<NaiveDate>,
+  ) -> Result<Vec<NaiveDate>, Phrase> {
+    let weeks = match self.weeks {
+      Some(weeks) => weeks.parse::<i64>().ok().filter(|weeks| *weeks > 0),
+      None => Some(1),
+    }
+    .ok_or(Phrase::WrongRecurrence)?;
+    let until = parse_date(self.until)
+      .filter(|until| *until >= first)
+      .ok_or(Phrase::WrongRecurrence)?;
+    let dates = (0..)
+      .map(|i| first + Duration::weeks(i * weeks))
+      .take_while(|date| *date <= until)
+      .collect::<Vec<_>>();
+    let except = self
+      .except
+      .map(|except| except.split(',').collect::<Vec<_>>())
+      .unwrap_or_default()
+      .into_iter()
+      .map(|date| {
+        parse_date(date.trim())
+          .filter(|date| dates.contains(date))
+          .ok_or(Phrase::WrongRecurrence)
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(
+      dates
+        .into_iter()
+        .filter(|date| !except.contains(date))
+        .collect(),
+    )
+  }
+}
+
+fn parse_time(time: &str) -> Option<(NaiveTime, Option<NaiveTime>)> {
   match time.split(&['-', '–']).collect::<Vec<_>>()[..] {
     [start, stop] => {
       let start_date = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
@@ -52,45 +225,12 @@ fn parse_time(time: &String) -> Option<(NaiveTime, Option<NaiveTime>)> {
   }
 }
 
-fn add_corresponding_stop_time(schedule_entries: Vec<ScheduleEntry>) -> Vec<ScheduleEntry> {
-  if schedule_entries.len() <= 1 {
-    return schedule_entries;
-  }
-
-  let mut previous_start_time = schedule_entries.last().unwrap().start_stop_time.0;
-  let mut previous_date = schedule_entries.last().unwrap().date;
-  let mut new_schedule_entries = vec![];
-
-  for (i, entry) in schedule_entries.into_iter().rev().enumerate() {
-    let previous_date_tmp = entry.date;
-    let previous_start_time_tmp = entry.start_stop_time.0;
-    if !previous_date.eq(&entry.date) || i == 0 {
-      new_schedule_entries.push(entry);
-    } else if entry.start_stop_time.1.is_none() {
-      new_schedule_entries.push(ScheduleEntry::new(
-        entry.date,
-        (entry.start_stop_time.0, Some(previous_start_time)),
-        entry.scenes,
-        entry.room,
-        entry.note,
-      ))
-    } else {
-      new_schedule_entries.push(entry);
-    }
-    previous_date = previous_date_tmp;
-    previous_start_time = previous_start_time_tmp;
-  }
-
-  new_schedule_entries.reverse();
-  new_schedule_entries
-}
-
 pub mod excel {
   use super::*;
   use calamine::{DataType, Range};
   use chrono::NaiveDate;
 
-  use crate::structures::{SceneEntry, ScheduleEntry};
+  use crate::structures::{Group, PersonInfo, SceneEntry, ScheduleEntry, Venue};
 
   pub fn parse_mandatory_silent_play_and_place(
     excel_range: &Range<DataType>,
@@ -101,18 +241,14 @@ pub mod excel {
       .rows()
       .next()
       .ok_or_else(|| SceneSchedulerError::ExcelError {
-        message: String::from(
-          "No first row found. Needs to contain at least the information about the location.",
-        ),
+        message: Phrase::NoFirstRow,
         file: file_path.to_owned(),
         sheet: sheet_name.to_owned(),
       })?;
 
     if first_row.len() < 2 {
       return Err(SceneSchedulerError::ExcelError {
-        message: String::from(
-          "Wrong Excel file format. First row should contain the information about the location.",
-        ),
+        message: Phrase::NoLocationInFirstRow,
         file: file_path.to_owned(),
         sheet: sheet_name.to_owned(),
       });
@@ -123,7 +259,7 @@ pub mod excel {
         sheet: sheet_name.to_owned(),
         row: 1,
         column: 2,
-        expected: String::from("The location should be specified."),
+        expected: Phrase::LocationMissing,
         token: first_row[1].to_string(),
       })?;
     let mandatory_silent_play = match parse_date_from_excel(&first_row[3]) {
@@ -134,7 +270,7 @@ pub mod excel {
           sheet: sheet_name.to_owned(),
           row: 1,
           column: 4,
-          expected: String::from("The date should be specified."),
+          expected: Phrase::DateMissing,
           token: first_row[3].to_string(),
         })
       }
@@ -143,21 +279,27 @@ pub mod excel {
     Ok((mandatory_silent_play, room))
   }
 
+  /// Parses the schedule sheet. An optional sixth column holds the type of
+  /// the entry, otherwise it is recognized from the scenes column.
   pub fn parse_schedule_plan_content(
     excel_range: &Range<DataType>,
     file_path: &str,
     sheet_name: &str,
+    entry_categories: &[EntryCategory],
   ) -> Result<Vec<ScheduleEntry>, SceneSchedulerError> {
     let mut start_parsing = false;
     let mut previous_date: Option<NaiveDate> = None;
     let mut schedule_entries = vec![];
+    // Number of entries of the last row with a date or time, as ranges and
+    // recurring rows are several entries.
+    let mut previous_entries = 0;
     for (i, row) in excel_range.rows().enumerate() {
       if i == 0 {
         continue;
       }
       if row.len() < 5 {
         return Err(SceneSchedulerError::ExcelError {
-          message: String::from("Wrong Excel file format. There should be 5 columns."),
+          message: Phrase::WrongNumberOfColumns,
           file: file_path.to_owned(),
           sheet: sheet_name.to_owned(),
         });
@@ -173,52 +315,164 @@ pub mod excel {
         }
       }
 
-      let date = match parse_date_from_excel(&row[0]) {
-        Some(Some(date)) => {
-          previous_date = Some(date);
-          date
+      // A row with nothing but scenes and a room adds a parallel session to
+      // the entries of the row above. The note of the row above applies to
+      // all sessions.
+      if row[0] == DataType::Empty && row[1] == DataType::Empty && row[2] != DataType::Empty {
+        if row[4] != DataType::Empty {
+          return Err(SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: 5,
+            expected: Phrase::WrongSessionNote,
+            token: row[4].to_string(),
+          });
+        }
+        let session_error = || SceneSchedulerError::ExcelParseError {
+          file: file_path.to_owned(),
+          sheet: sheet_name.to_owned(),
+          row: i + 1,
+          column: 3,
+          expected: Phrase::WrongSessionScenes,
+          token: row[2].to_string(),
+        };
+        let session = match parse_scenes_from_excel(&row[2]) {
+          Scenes::Normal(scenes) if !scenes.is_empty() => Session {
+            scenes,
+            room: parse_room_from_excel(&row[3]),
+          },
+          _ => return Err(session_error()),
+        };
+        if previous_entries == 0 {
+          return Err(session_error());
+        }
+        let previous = schedule_entries.split_off(schedule_entries.len() - previous_entries);
+        for previous in previous {
+          let mut sessions = sessions_of(&previous).ok_or_else(session_error)?;
+          sessions.push(session.clone());
+          let schedule_entry = ScheduleEntry::with_sessions(
+            previous.date,
+            previous.start_stop_time,
+            sessions,
+            previous.note,
+          )
+          .with_type(previous.entry_type);
+          schedule_entries.push(match previous.all_day_until {
+            Some(last_date) => schedule_entry.all_day(last_date),
+            None => schedule_entry,
+          });
+        }
+        continue;
+      }
+
+      let days = match parse_dates_from_excel(&row[0]) {
+        Some(Ok(days)) => {
+          previous_date = days.first().map(|(date, _)| *date);
+          days
         }
-        Some(None) => {
+        Some(Err(expected)) => {
           return Err(SceneSchedulerError::ExcelParseError {
             file: file_path.to_owned(),
             sheet: sheet_name.to_owned(),
             row: i + 1,
             column: 1,
-            expected: String::from("Wrong date string format should be DD.MM.YY., e.g. 01.01.22."),
+            expected,
             token: row[0].to_string(),
           })
         }
-        None => previous_date.ok_or_else(|| SceneSchedulerError::ExcelParseError {
-          file: file_path.to_owned(),
-          sheet: sheet_name.to_owned(),
-          row: i + 1,
-          column: 1,
-          expected: String::from("The date should be specified."),
-          token: row[0].to_string(),
-        })?,
+        None => previous_date
+          .map(|date| vec![(date, date)])
+          .ok_or_else(|| SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: 1,
+            expected: Phrase::DateMissing,
+            token: row[0].to_string(),
+          })?,
       };
-      let start_stop_time =
-        parse_time_from_excel(&row[1]).ok_or_else(|| SceneSchedulerError::ExcelParseError {
-          file: file_path.to_owned(),
-          sheet: sheet_name.to_owned(),
-          row: i + 1,
-          column: 2,
-          expected: String::from("Wrong time string format should be HH:MM, e.g. 12:00"),
-          token: row[1].to_string(),
+      // A row with a date but without a time lasts the whole day.
+      let start_stop_time = match parse_time_from_excel(&row[1]) {
+        Some(start_stop_time) => Some(start_stop_time),
+        None if row[1] == DataType::Empty && row[0] != DataType::Empty => None,
+        None => {
+          return Err(SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: 2,
+            expected: Phrase::WrongTimeFormat,
+            token: row[1].to_string(),
+          })
+        }
+      };
+      let note = parse_note_from_excel(&row[4]);
+      let sessions =
+        parse_sessions(&row[2].to_string(), &row[3].to_string()).map_err(|expected| {
+          SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: if expected == Phrase::WrongSessionRooms {
+              4
+            } else {
+              3
+            },
+            expected,
+            token: row[2].to_string(),
+          }
         })?;
       let scenes = parse_scenes_from_excel(&row[2]);
-      let room = parse_room_from_excel(&row[3]);
-      let note = parse_note_from_excel(&row[4]);
-
-      schedule_entries.push(ScheduleEntry::new(
-        date,
-        start_stop_time,
-        scenes,
-        room,
-        note,
-      ));
+      let type_name = row
+        .get(5)
+        .filter(|type_name| **type_name != DataType::Empty)
+        .map(|type_name| type_name.to_string());
+      let entry_type =
+        parse_entry_type(&scenes, type_name.as_deref(), entry_categories).map_err(|expected| {
+          SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: 6,
+            expected,
+            token: type_name.clone().unwrap_or_default(),
+          }
+        })?;
+      let schedule_entry = |date: NaiveDate, start_stop_time| {
+        match &sessions {
+          Some(sessions) => {
+            ScheduleEntry::with_sessions(date, start_stop_time, sessions.clone(), note.clone())
+          }
+          None => ScheduleEntry::new(
+            date,
+            start_stop_time,
+            scenes.clone(),
+            parse_room_from_excel(&row[3]),
+            note.clone(),
+          ),
+        }
+        .with_type(entry_type)
+      };
+      // Recurring entries are one entry per occurrence.
+      let entries_before = schedule_entries.len();
+      for (date, last_date) in days {
+        match start_stop_time {
+          Some(start_stop_time) => {
+            // Several days with a time, e.g. a run of performances, are one
+            // entry per day.
+            for date in date.iter_days().take_while(|date| *date <= last_date) {
+              schedule_entries.push(schedule_entry(date, start_stop_time));
+            }
+          }
+          None => {
+            schedule_entries.push(schedule_entry(date, (NaiveTime::MIN, None)).all_day(last_date))
+          }
+        }
+      }
+      previous_entries = schedule_entries.len() - entries_before;
     }
-    Ok(add_corresponding_stop_time(schedule_entries))
+    Ok(schedule_entries)
   }
 
   fn parse_note_from_excel(note: &DataType) -> Option<Note> {
@@ -248,6 +502,31 @@ pub mod excel {
     }
   }
 
+  /// Like `parse_date_from_excel` but also accepts a range of days and a
+  /// recurring date. Returns the first and the last day of every entry, a
+  /// single date is both.
+  fn parse_dates_from_excel(
+    date: &DataType,
+  ) -> Option<Result<Vec<(NaiveDate, NaiveDate)>, Phrase>> {
+    if let DataType::String(dates) = date {
+      if let Some(range) = parse_date_range(dates) {
+        return Some(Ok(vec![range]));
+      }
+      if let Some((first, recurrence)) = split_recurrence(dates) {
+        let dates = parse_date(first)
+          .ok_or(Phrase::WrongDateFormat)
+          .and_then(|first| recurrence.dates(first, parse_recurrence_date))
+          .map(|dates| dates.into_iter().map(|date| (date, date)).collect());
+        return Some(dates);
+      }
+    }
+    parse_date_from_excel(date).map(|date| {
+      date
+        .map(|date| vec![(date, date)])
+        .ok_or(Phrase::WrongDateFormat)
+    })
+  }
+
   fn parse_date_from_excel(date: &DataType) -> Option<Option<NaiveDate>> {
     if date == &DataType::Empty || date.to_string().trim().is_empty() {
       return None; // TODO: Make this better
@@ -267,6 +546,185 @@ pub mod excel {
     }
   }
 
+  /// Parses the groups sheet. The first row is a header. Every other row
+  /// contains the group name in the first column followed by its members.
+  /// If the group name is left empty, the members belong to the group above.
+  pub fn parse_groups_content(
+    excel_range: &Range<DataType>,
+    file_path: &str,
+    sheet_name: &str,
+  ) -> Result<Vec<Group>, SceneSchedulerError> {
+    let mut groups: Vec<Group> = vec![];
+    for (i, row) in excel_range.rows().enumerate().skip(1) {
+      match &row[0] {
+        DataType::String(name) if !name.trim().is_empty() => groups.push(Group {
+          name: name.trim().to_owned(),
+          members: vec![],
+        }),
+        DataType::Empty => {}
+        _ => {
+          return Err(SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: 1,
+            expected: Phrase::WrongGroupName,
+            token: row[0].to_string(),
+          })
+        }
+      }
+      for (column, member) in row.iter().enumerate().skip(1) {
+        let members = match member {
+          DataType::String(members) => members,
+          DataType::Empty => continue,
+          _ => {
+            return Err(SceneSchedulerError::ExcelParseError {
+              file: file_path.to_owned(),
+              sheet: sheet_name.to_owned(),
+              row: i + 1,
+              column: column + 1,
+              expected: Phrase::WrongGroupMember,
+              token: member.to_string(),
+            })
+          }
+        };
+        let group = groups
+          .last_mut()
+          .ok_or_else(|| SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: 1,
+            expected: Phrase::WrongGroupName,
+            token: row[0].to_string(),
+          })?;
+        group.members.extend(
+          members
+            .split(',')
+            .map(|m| m.trim().to_owned())
+            .filter(|m| !m.is_empty()),
+        );
+      }
+    }
+    Ok(groups)
+  }
+
+  /// Parses the people sheet. The first row is a header. The columns are
+  /// name, aliases (comma separated), email and phone.
+  pub fn parse_people_content(
+    excel_range: &Range<DataType>,
+    file_path: &str,
+    sheet_name: &str,
+  ) -> Result<Vec<PersonInfo>, SceneSchedulerError> {
+    let mut people = vec![];
+    for (i, row) in excel_range.rows().enumerate().skip(1) {
+      let name = match &row[0] {
+        DataType::String(name) if !name.trim().is_empty() => name.trim().to_owned(),
+        DataType::Empty => continue,
+        _ => {
+          return Err(SceneSchedulerError::ExcelParseError {
+            file: file_path.to_owned(),
+            sheet: sheet_name.to_owned(),
+            row: i + 1,
+            column: 1,
+            expected: Phrase::WrongPersonName,
+            token: row[0].to_string(),
+          })
+        }
+      };
+      let cell = |column: usize| {
+        row
+          .get(column)
+          .and_then(parse_text_from_excel)
+          .filter(|text| !text.is_empty())
+      };
+      let aliases = cell(1)
+        .map(|aliases| {
+          aliases
+            .split(',')
+            .map(|alias| alias.trim().to_owned())
+            .filter(|alias| !alias.is_empty())
+            .collect()
+        })
+        .unwrap_or_default();
+      people.push(PersonInfo {
+        name,
+        aliases,
+        email: cell(2),
+        phone: cell(3),
+      });
+    }
+    Ok(people)
+  }
+
+  /// Parses the venues sheet. The first row is a header. The columns are
+  /// room, address, building, floor, directions, latitude and longitude.
+  pub fn parse_venues_content(
+    excel_range: &Range<DataType>,
+    file_path: &str,
+    sheet_name: &str,
+  ) -> Result<Vec<Venue>, SceneSchedulerError> {
+    let mut venues = vec![];
+    for (i, row) in excel_range.rows().enumerate().skip(1) {
+      let error = |column: usize, expected: Phrase| SceneSchedulerError::ExcelParseError {
+        file: file_path.to_owned(),
+        sheet: sheet_name.to_owned(),
+        row: i + 1,
+        column: column + 1,
+        expected,
+        token: row
+          .get(column)
+          .map(|cell| cell.to_string())
+          .unwrap_or_default(),
+      };
+      let room = match &row[0] {
+        DataType::String(room) if !room.trim().is_empty() => parse_room(room),
+        DataType::Empty => continue,
+        _ => return Err(error(0, Phrase::WrongRoomName)),
+      };
+      let cell = |column: usize| {
+        row
+          .get(column)
+          .and_then(parse_text_from_excel)
+          .filter(|text| !text.is_empty())
+      };
+      let degrees = |column: usize| match row.get(column) {
+        None | Some(DataType::Empty) => Ok(None),
+        Some(DataType::Float(degrees)) => Ok(Some(*degrees)),
+        Some(DataType::Int(degrees)) => Ok(Some(*degrees as f64)),
+        Some(degrees) => match degrees.to_string().trim().parse() {
+          Ok(degrees) => Ok(Some(degrees)),
+          Err(_) => Err(error(column, Phrase::WrongCoordinates)),
+        },
+      };
+      let coordinates = match (degrees(5)?, degrees(6)?) {
+        (Some(latitude), Some(longitude)) => Some((latitude, longitude)),
+        (None, None) => None,
+        (Some(_), None) => return Err(error(6, Phrase::WrongCoordinates)),
+        (None, Some(_)) => return Err(error(5, Phrase::WrongCoordinates)),
+      };
+      venues.push(Venue {
+        room,
+        address: cell(1),
+        building: cell(2),
+        floor: cell(3),
+        directions: cell(4),
+        coordinates,
+      });
+    }
+    Ok(venues)
+  }
+
+  fn parse_text_from_excel(text: &DataType) -> Option<String> {
+    match text {
+      DataType::Empty => None,
+      // Phone numbers are often stored as numbers
+      DataType::Float(number) if number.fract() == 0.0 => Some(format!("{:.0}", number)),
+      DataType::Int(number) => Some(number.to_string()),
+      _ => Some(text.to_string().trim().to_owned()),
+    }
+  }
+
   pub fn parse_scene_plan_content(
     excel_range: Range<DataType>,
     file_path: &str,
@@ -277,8 +735,7 @@ pub mod excel {
     let scene_start_index = 2;
     for (i, row) in excel_range.rows().enumerate() {
       if i == 0 {
-        let mut column_index = scene_start_index;
-        for scene in &row[scene_start_index..] {
+        for (column_index, scene) in (scene_start_index..).zip(row[scene_start_index..].iter()) {
           match scene {
             DataType::String(x) => all_scenes.push(x.to_owned()),
             DataType::Float(x) => all_scenes.push(x.to_string()),
@@ -288,12 +745,11 @@ pub mod excel {
                 sheet: sheet_name.to_owned(),
                 row: i + 1,
                 column: column_index + 1,
-                expected: String::from("Scene name should be a string or a float."),
+                expected: Phrase::WrongSceneName,
                 token: row[0].to_string(),
               })
             }
           }
-          column_index += 1;
         }
       } else {
         if row[0] == DataType::Empty && row[1] == DataType::Empty {
@@ -308,7 +764,7 @@ pub mod excel {
               sheet: sheet_name.to_owned(),
               row: i + 1,
               column: 1,
-              expected: String::from("Role should be a string."),
+              expected: Phrase::WrongRole,
               token: row[0].to_string(),
             })
           }
@@ -321,7 +777,7 @@ pub mod excel {
               sheet: sheet_name.to_owned(),
               row: i + 1,
               column: 2,
-              expected: String::from("Person who plays the role should be a string."),
+              expected: Phrase::WrongPerson,
               token: row[1].to_string(),
             })
           }
@@ -339,14 +795,419 @@ pub mod excel {
             }
           }
         }
-        scene_entries.push(SceneEntry {
+        // Double cast roles list all cast members separated by a slash.
+        let mut cast = who
+          .split('/')
+          .map(|person| person.trim().to_owned())
+          .filter(|person| !person.is_empty());
+        let mut scene_entry = SceneEntry::new(
           role,
-          who,
-          scenes: scenes_for_current_role,
+          cast.next().unwrap_or(who.clone()),
+          scenes_for_current_role,
           silent_play,
-        })
+        );
+        scene_entry.alternates = cast.collect();
+        scene_entries.push(scene_entry)
       }
     }
     Ok(scene_entries)
   }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    fn schedule_sheet(rows: &[[&str; 5]]) -> Range<DataType> {
+      let mut range = Range::new((0, 0), (rows.len() as u32 + 1, 4));
+      range.set_value((1, 0), DataType::String("Datum".to_string()));
+      for (i, row) in rows.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+          if !cell.is_empty() {
+            range.set_value((i as u32 + 2, j as u32), DataType::String(cell.to_string()));
+          }
+        }
+      }
+      range
+    }
+
+    #[test]
+    fn test_sessions_of_recurring_rows() {
+      let sheet = schedule_sheet(&[
+        [
+          "05.03.24 wöchentlich bis 12.03.24",
+          "18:00",
+          "3",
+          "Saal",
+          "",
+        ],
+        ["", "", "6", "Foyer", ""],
+      ]);
+      let schedule_entries =
+        parse_schedule_plan_content(&sheet, "plan.xlsx", "Probenplan", &[]).unwrap();
+      assert_eq!(schedule_entries.len(), 2);
+      for schedule_entry in &schedule_entries {
+        assert_eq!(
+          schedule_entry
+            .sessions
+            .iter()
+            .map(|session| session.room.as_deref())
+            .collect::<Vec<_>>(),
+          vec![Some("Saal"), Some("Foyer")]
+        );
+      }
+
+      let sheet = schedule_sheet(&[
+        ["05.03.24", "18:00", "3", "Saal", ""],
+        ["", "", "6", "Foyer", "Mit Noten"],
+      ]);
+      assert!(matches!(
+        parse_schedule_plan_content(&sheet, "plan.xlsx", "Probenplan", &[]),
+        Err(SceneSchedulerError::ExcelParseError {
+          row: 4,
+          column: 5,
+          expected: Phrase::WrongSessionNote,
+          ..
+        })
+      ));
+    }
+
+    #[test]
+    fn test_recurrence_until_plan_date() {
+      let sheet = schedule_sheet(&[[
+        "05.03.24 every 2 weeks until 2024-05-28 except 2024-04-16",
+        "18:00",
+        "3",
+        "",
+        "",
+      ]]);
+      let schedule_entries =
+        parse_schedule_plan_content(&sheet, "plan.xlsx", "Probenplan", &[]).unwrap();
+      assert_eq!(
+        schedule_entries
+          .iter()
+          .map(|schedule_entry| schedule_entry.date.format("%d.%m.").to_string())
+          .collect::<Vec<_>>(),
+        vec!["05.03.", "19.03.", "02.04.", "30.04.", "14.05.", "28.05."]
+      );
+    }
+  }
+}
+
+pub mod calendar {
+  use super::*;
+  use crate::structures::CalendarImport;
+  use chrono::{NaiveDateTime, TimeZone, Utc};
+  use chrono_tz::{Europe::Zurich, Tz};
+  use ical::parser::ical::component::IcalEvent;
+  use ical::property::Property;
+
+  const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+  const DATE_FORMAT: &str = "%Y%m%d";
+
+  /// Turns the events of an imported calendar into schedule entries. The
+  /// times are converted to the local time of the schedule. The type is
+  /// taken from `CATEGORIES` if it names one.
+  pub fn parse_calendar_events(
+    events: &[IcalEvent],
+    import: &CalendarImport,
+    entry_categories: &[EntryCategory],
+  ) -> Result<Vec<ScheduleEntry>, SceneSchedulerError> {
+    let error = |message: String| SceneSchedulerError::CalendarImport {
+      file: import.path.clone(),
+      message,
+    };
+    let compile = |pattern: &Option<String>| {
+      pattern
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| error(e.to_string()))
+    };
+    let summary_pattern = compile(&import.summary_pattern)?;
+    let description_pattern = compile(&import.description_pattern)?;
+
+    let mut schedule_entries = vec![];
+    for event in events {
+      let summary = text(event, "SUMMARY").unwrap_or_default();
+      let scenes = match &summary_pattern {
+        Some(pattern) => match captured(pattern, &summary, "scenes") {
+          Some(scenes) => parse_scenes(scenes),
+          None => continue,
+        },
+        None => parse_scenes(&summary),
+      };
+      let note = text(event, "DESCRIPTION")
+        .and_then(|description| match &description_pattern {
+          Some(pattern) => captured(pattern, &description, "note").map(parse_note),
+          None => Some(parse_note(&description)),
+        })
+        .filter(|note| !note.is_empty());
+      let room = text(event, "LOCATION")
+        .map(|location| parse_room(&location))
+        .filter(|room| !room.is_empty());
+      let entry_type = text(event, "CATEGORIES")
+        .and_then(|categories| {
+          categories
+            .split(',')
+            .find_map(|category| find_entry_type(category, entry_categories))
+        })
+        .unwrap_or_else(|| parse_entry_type(&scenes, None, entry_categories).unwrap_or_default());
+      let start = match date_time(event, "DTSTART").map_err(error)? {
+        Some(start) => start,
+        None => {
+          let first_date = match date(event, "DTSTART").map_err(error)? {
+            Some(first_date) => first_date,
+            None => continue,
+          };
+          // The end of all-day events is the day after the last one.
+          let last_date = date(event, "DTEND")
+            .map_err(error)?
+            .and_then(|end| end.pred_opt())
+            .unwrap_or(first_date);
+          schedule_entries.push(
+            ScheduleEntry::new(first_date, (NaiveTime::MIN, None), scenes, room, note)
+              .all_day(last_date)
+              .with_type(entry_type),
+          );
+          continue;
+        }
+      };
+      // Entries end on the day they start.
+      let stop = date_time(event, "DTEND")
+        .map_err(error)?
+        .filter(|stop| stop.date() == start.date() && *stop > start)
+        .map(|stop| stop.time());
+      schedule_entries.push(
+        ScheduleEntry::new(start.date(), (start.time(), stop), scenes, room, note)
+          .with_type(entry_type),
+      );
+    }
+    Ok(schedule_entries)
+  }
+
+  fn property<'a>(event: &'a IcalEvent, name: &str) -> Option<&'a Property> {
+    event
+      .properties
+      .iter()
+      .find(|property| property.name.eq_ignore_ascii_case(name))
+  }
+
+  fn parameter<'a>(property: &'a Property, name: &str) -> Option<&'a str> {
+    property
+      .params
+      .as_ref()?
+      .iter()
+      .find(|(param, _)| param.eq_ignore_ascii_case(name))
+      .and_then(|(_, values)| values.first())
+      .map(String::as_str)
+  }
+
+  fn text(event: &IcalEvent, name: &str) -> Option<String> {
+    property(event, name)
+      .and_then(|property| property.value.as_deref())
+      .map(unescape)
+  }
+
+  /// The named group if the pattern has one, otherwise the whole match.
+  fn captured<'a>(pattern: &Regex, text: &'a str, group: &str) -> Option<&'a str> {
+    let captures = pattern.captures(text)?;
+    captures
+      .name(group)
+      .or_else(|| captures.get(0))
+      .map(|m| m.as_str())
+  }
+
+  /// The local date and time of the property, `None` for dates without a
+  /// time.
+  fn date_time(event: &IcalEvent, name: &str) -> Result<Option<NaiveDateTime>, String> {
+    let property = match property(event, name) {
+      Some(property) => property,
+      None => return Ok(None),
+    };
+    let value = property.value.as_deref().unwrap_or_default().trim();
+    if parameter(property, "VALUE") == Some("DATE") || !value.contains('T') {
+      return Ok(None);
+    }
+    let invalid = || format!("Invalid {} '{}'.", name, value);
+    let naive = NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), DATE_TIME_FORMAT)
+      .map_err(|_| invalid())?;
+    let local = if value.ends_with('Z') {
+      Utc.from_utc_datetime(&naive).with_timezone(&Zurich)
+    } else {
+      match parameter(property, "TZID").and_then(|tzid| tzid.parse::<Tz>().ok()) {
+        Some(tz) => tz
+          .from_local_datetime(&naive)
+          .earliest()
+          .ok_or_else(invalid)?
+          .with_timezone(&Zurich),
+        // Floating times are taken as they are.
+        None => return Ok(Some(naive)),
+      }
+    };
+    Ok(Some(local.naive_local()))
+  }
+
+  /// The date of a property without a time, `None` for dates with a time.
+  fn date(event: &IcalEvent, name: &str) -> Result<Option<NaiveDate>, String> {
+    let value = match property(event, name).and_then(|property| property.value.as_deref()) {
+      Some(value) if !value.contains('T') => value.trim(),
+      _ => return Ok(None),
+    };
+    NaiveDate::parse_from_str(value, DATE_FORMAT)
+      .map(Some)
+      .map_err(|_| format!("Invalid {} '{}'.", name, value))
+  }
+
+  /// Reverts the escaping of `TEXT` values.
+  fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+      if c != '\\' {
+        unescaped.push(c);
+        continue;
+      }
+      match chars.next() {
+        Some('n') | Some('N') => unescaped.push('\n'),
+        Some(c) => unescaped.push(c),
+        None => unescaped.push('\\'),
+      }
+    }
+    unescaped
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+    use crate::structures::ImportMode;
+    use ical::IcalParser;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTART:20220501T080000Z\r
+DTEND:20220501T110000Z\r
+SUMMARY:Probe Szenen 1\\, 2\r
+DESCRIPTION:Gebucht von: Regie\\nMit Kostüm\r
+LOCATION:Probebühne\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2\r
+DTSTART;TZID=Europe/Zurich:20220502T190000\r
+SUMMARY:Probe Hauptprobe\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:3\r
+DTSTART:20220503T100000\r
+SUMMARY:Reinigung\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:4\r
+DTSTART;VALUE=DATE:20220504\r
+SUMMARY:Probe 3\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_calendar_events() {
+      let events = IcalParser::new(CALENDAR.as_bytes())
+        .flat_map(|calendar| calendar.unwrap().events)
+        .collect::<Vec<_>>();
+      let import = CalendarImport {
+        path: "venue.ics".to_string(),
+        mode: ImportMode::Merge,
+        summary_pattern: Some(r"^Probe (?:Szenen )?(?P<scenes>.+)$".to_string()),
+        description_pattern: Some(r"(?m)^Mit (?P<note>.+)$".to_string()),
+      };
+      let schedule_entries =
+        parse_calendar_events(&events, &import, &EntryCategory::defaults()).unwrap();
+      assert_eq!(schedule_entries.len(), 3);
+
+      let first = &schedule_entries[0];
+      assert_eq!(
+        first.start_stop_date_time(),
+        (
+          NaiveDate::from_ymd_opt(2022, 5, 1)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap(),
+          NaiveDate::from_ymd_opt(2022, 5, 1)
+            .unwrap()
+            .and_hms_opt(13, 0, 0)
+        )
+      );
+      assert_eq!(
+        first.scenes,
+        Scenes::Normal(vec!["1".to_string(), "2".to_string()])
+      );
+      assert_eq!(first.room.as_deref(), Some("Probebühne"));
+      assert_eq!(first.note.as_deref(), Some("Kostüm"));
+
+      let second = &schedule_entries[1];
+      assert_eq!(second.scenes, Scenes::Special("Hauptprobe".to_string()));
+      assert_eq!(
+        second.start_stop_time,
+        (NaiveTime::from_hms_opt(19, 0, 0).unwrap(), None)
+      );
+      assert_eq!(second.room, None);
+
+      let all_day = &schedule_entries[2];
+      assert!(all_day.is_all_day());
+      assert_eq!(all_day.scenes, Scenes::Normal(vec!["3".to_string()]));
+      assert_eq!(
+        all_day.last_date(),
+        NaiveDate::from_ymd_opt(2022, 5, 4).unwrap()
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_date_range() {
+    let date = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+    for dates in [
+      "12.–14.04.24",
+      "Fr 12.04. - So 14.04.24",
+      "12.04.24-14.04.24",
+    ] {
+      assert_eq!(
+        parse_date_range(dates),
+        Some((date(12), date(14))),
+        "{}",
+        dates
+      );
+    }
+    assert_eq!(parse_date_range("14.–12.04.24"), None);
+    assert_eq!(parse_date_range("12.04.24"), None);
+  }
+
+  #[test]
+  fn test_recurrence() {
+    let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+    let (first, recurrence) =
+      split_recurrence("Di 05.03.24 wöchentlich bis 02.04.24 ohne 19.03.24").unwrap();
+    assert_eq!(first, "Di 05.03.24 ");
+    assert_eq!(
+      recurrence.dates(parse_date(first).unwrap(), parse_date),
+      Ok(vec![date(3, 5), date(3, 12), date(3, 26), date(4, 2)])
+    );
+    let (_, recurrence) = split_recurrence("05.03.24 every 2 weeks until 02.04.24").unwrap();
+    assert_eq!(
+      recurrence.dates(date(3, 5), parse_date),
+      Ok(vec![date(3, 5), date(3, 19), date(4, 2)])
+    );
+    let (_, recurrence) =
+      split_recurrence("05.03.24 weekly until 02.04.24 except 20.03.24").unwrap();
+    assert_eq!(
+      recurrence.dates(date(3, 5), parse_date),
+      Err(Phrase::WrongRecurrence)
+    );
+    assert!(split_recurrence("05.03.24").is_none());
+  }
 }
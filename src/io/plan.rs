@@ -0,0 +1,1043 @@
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::i18n::Phrase;
+use crate::io::parsing::{parse_entry_type, split_recurrence};
+use crate::model::Model;
+use crate::sorting::find_entry_type;
+use crate::structures::{
+  EntryCategory, Group, SceneEntry, SceneSchedulerError, Scenes, ScheduleEntry, Session, Venue,
+};
+
+pub const PLAN_EXTENSION: &str = "plan";
+/// Marks a list of scenes, e.g. `Sz 3, 5`. Lists starting with a digit may
+/// leave it out.
+const SCENES_PREFIX: &str = "Sz";
+const COMMENT: char = '#';
+const FIELD_SEPARATOR: char = '|';
+const SETTING_SEPARATOR: char = ':';
+const LIST_SEPARATORS: [char; 2] = [',', '/'];
+const CAST_SEPARATOR: char = '/';
+/// Separates parallel sessions, e.g. `Sz 3, 4; Sz 7 | Saal; Raum B`.
+const SESSION_SEPARATOR: char = ';';
+const TIME_SEPARATORS: [char; 2] = ['-', '–'];
+/// Takes the next character literally, e.g. `\;` in a room or `\2. Leseprobe`
+/// for a special entry starting with a digit.
+const ESCAPE: char = '\\';
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Between the first and the last of several days.
+const DATE_RANGE_SEPARATOR: &str = "..";
+const TIME_FORMAT: &str = "%H:%M";
+const LOCATION_SETTING: &str = "location";
+const SILENT_PLAY_SETTING: &str = "silent play from";
+const SCHEDULE_SECTION: &str = "schedule";
+const CAST_SECTION: &str = "cast";
+const GROUPS_SECTION: &str = "groups";
+const VENUES_SECTION: &str = "venues";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+  Settings,
+  Schedule,
+  Cast,
+  Groups,
+  Venues,
+}
+
+/// A trimmed part of a line together with the column it starts in, counted
+/// from 1.
+#[derive(Debug, Clone, Copy)]
+struct Field<'a> {
+  text: &'a str,
+  column: usize,
+}
+
+impl<'a> Field<'a> {
+  fn new(line: &'a str) -> Self {
+    Field {
+      text: line,
+      column: 1,
+    }
+  }
+
+  /// Splits the field into at most `limit` trimmed parts. Escaped
+  /// separators are not split at.
+  fn split(&self, separator: &[char], limit: usize) -> Vec<Field<'a>> {
+    let mut fields = vec![];
+    let mut start = 0;
+    let mut chars = self.text.char_indices();
+    while let Some((index, c)) = chars.next() {
+      if fields.len() + 1 >= limit {
+        break;
+      }
+      if c == ESCAPE {
+        chars.next();
+      } else if separator.contains(&c) {
+        fields.push(self.part(start, index));
+        start = index + c.len_utf8();
+      }
+    }
+    fields.push(self.part(start, self.text.len()));
+    fields
+  }
+
+  /// The trimmed part between the byte offsets `start` and `end`.
+  fn part(&self, start: usize, end: usize) -> Field<'a> {
+    let part = &self.text[start..end];
+    let leading = part.len() - part.trim_start().len();
+    Field {
+      text: part.trim(),
+      column: self.column + self.text[..start + leading].chars().count(),
+    }
+  }
+
+  /// The trimmed parts before and after the first `separator`.
+  fn split_once(&self, separator: &str) -> Option<(Field<'a>, Field<'a>)> {
+    let index = self.text.find(separator)?;
+    let before = Field {
+      text: &self.text[..index],
+      column: self.column,
+    };
+    let after = Field {
+      text: &self.text[index + separator.len()..],
+      column: self.column + self.text[..index + separator.len()].chars().count(),
+    };
+    Some((before.split(&[], 1)[0], after.split(&[], 1)[0]))
+  }
+
+  /// Column right after the field, e.g. for missing fields.
+  fn end(&self) -> usize {
+    self.column + self.text.chars().count()
+  }
+}
+
+struct Parser<'a> {
+  file: &'a str,
+  line: usize,
+  entry_categories: &'a [EntryCategory],
+}
+
+impl Parser<'_> {
+  fn error(&self, column: usize, expected: Phrase, token: &str) -> SceneSchedulerError {
+    SceneSchedulerError::PlanParseError {
+      file: self.file.to_owned(),
+      line: self.line,
+      column,
+      expected,
+      token: token.to_owned(),
+    }
+  }
+
+  fn date(&self, field: Field) -> Result<NaiveDate, SceneSchedulerError> {
+    NaiveDate::parse_from_str(field.text, DATE_FORMAT)
+      .map_err(|_| self.error(field.column, Phrase::PlanWrongDate, field.text))
+  }
+
+  /// A single date or the first and the last of several days.
+  fn dates(&self, field: Field) -> Result<(NaiveDate, NaiveDate), SceneSchedulerError> {
+    let (first, last) = match field.split_once(DATE_RANGE_SEPARATOR) {
+      Some((first, last)) => (self.date(first)?, last),
+      None => return self.date(field).map(|date| (date, date)),
+    };
+    match self.date(last)? {
+      last_date if last_date >= first => Ok((first, last_date)),
+      _ => Err(self.error(last.column, Phrase::PlanWrongDate, last.text)),
+    }
+  }
+
+  fn time(&self, field: Field) -> Result<(NaiveTime, Option<NaiveTime>), SceneSchedulerError> {
+    let times = field.split(&TIME_SEPARATORS, 2);
+    let parse = |time: Field| {
+      NaiveTime::parse_from_str(time.text, TIME_FORMAT)
+        .map_err(|_| self.error(time.column, Phrase::WrongTimeFormat, time.text))
+    };
+    let start = parse(times[0])?;
+    let stop = times.get(1).map(|stop| parse(*stop)).transpose()?;
+    Ok((start, stop))
+  }
+
+  /// Parallel sessions with their scenes and rooms separated by `;`. A
+  /// missing room means the default location.
+  fn sessions(
+    &self,
+    scenes: Field,
+    rooms: Option<&Field>,
+  ) -> Result<Vec<Session>, SceneSchedulerError> {
+    let rooms = rooms
+      .map(|rooms| rooms.split(&[SESSION_SEPARATOR], usize::MAX))
+      .unwrap_or_default();
+    let scenes = scenes.split(&[SESSION_SEPARATOR], usize::MAX);
+    if rooms.len() > scenes.len() {
+      let room = rooms[scenes.len()];
+      return Err(self.error(room.column, Phrase::WrongSessionRooms, room.text));
+    }
+    scenes
+      .iter()
+      .enumerate()
+      .map(|(i, field)| match scene_list(field.text) {
+        Some(scenes) if !scenes.is_empty() => Ok(Session {
+          scenes,
+          room: rooms
+            .get(i)
+            .filter(|room| !room.text.is_empty())
+            .map(|room| unescape(room.text)),
+        }),
+        _ => Err(self.error(field.column, Phrase::WrongSessionScenes, field.text)),
+      })
+      .collect()
+  }
+
+  /// `2024-03-05 18:00-21:00 | Sz 3, 5 | Saal | note`. Everything after the
+  /// date is optional, entries without a time last the whole day. Several
+  /// days like `2024-04-12..2024-04-14` with a time are one entry per day,
+  /// `2024-03-05 19:00 weekly until 2024-05-28 except 2024-04-02` one entry
+  /// per week. The scenes may be preceded by a type, e.g.
+  /// `Hauptprobe: Sz 1, 2`. Separators in the name of a special entry, the
+  /// rooms and the note are escaped by `\`.
+  fn schedule_entries(&self, line: Field) -> Result<Vec<ScheduleEntry>, SceneSchedulerError> {
+    let mut fields = line.split(&[FIELD_SEPARATOR], 4);
+    let mut entry_type = None;
+    if let Some(scenes) = fields.get_mut(1) {
+      if let [name, rest] = scenes.split(&[SETTING_SEPARATOR], 2)[..] {
+        entry_type = find_entry_type(name.text, self.entry_categories);
+        if entry_type.is_some() {
+          *scenes = rest;
+        }
+      }
+    }
+    let (when, recurrence) = match split_recurrence(fields[0].text) {
+      Some((when, recurrence)) => {
+        let rule = Field {
+          text: &fields[0].text[when.len()..],
+          column: fields[0].column + when.chars().count(),
+        };
+        let when = Field {
+          text: when.trim_end(),
+          column: fields[0].column,
+        };
+        (when, Some((rule, recurrence)))
+      }
+      None => (fields[0], None),
+    };
+    let date_time = when.split(&[' ', '\t'], 2);
+    let (date, last_date) = self.dates(date_time[0])?;
+    let days = match recurrence {
+      Some((rule, _)) if last_date != date => {
+        return Err(self.error(rule.column, Phrase::WrongRecurrence, rule.text))
+      }
+      Some((rule, recurrence)) => recurrence
+        .dates(date, |date| {
+          NaiveDate::parse_from_str(date, DATE_FORMAT).ok()
+        })
+        .map_err(|expected| self.error(rule.column, expected, rule.text))?
+        .into_iter()
+        .map(|date| (date, date))
+        .collect(),
+      None => vec![(date, last_date)],
+    };
+    let time = date_time
+      .get(1)
+      .filter(|time| !time.text.is_empty())
+      .map(|time| self.time(*time))
+      .transpose()?;
+    let optional = |index: usize| {
+      fields
+        .get(index)
+        .filter(|field| !field.text.is_empty())
+        .map(|field| unescape(field.text))
+    };
+    let is_split = |index: usize| match fields.get(index) {
+      Some(field) => field.split(&[SESSION_SEPARATOR], 2).len() > 1,
+      None => false,
+    };
+    let sessions = if is_split(1) || is_split(2) {
+      Some(self.sessions(fields[1], fields.get(2))?)
+    } else {
+      None
+    };
+    let scenes = match fields.get(1) {
+      Some(scenes) => match scene_list(scenes.text) {
+        Some(scenes) => Scenes::Normal(scenes),
+        None => Scenes::Special(unescape(scenes.text)),
+      },
+      None => Scenes::Normal(vec![]),
+    };
+    let entry_type = entry_type.unwrap_or_else(|| {
+      parse_entry_type(&scenes, None, self.entry_categories).unwrap_or_default()
+    });
+    let schedule_entry = |date: NaiveDate, time| {
+      match &sessions {
+        Some(sessions) => ScheduleEntry::with_sessions(date, time, sessions.clone(), optional(3)),
+        None => ScheduleEntry::new(date, time, scenes.clone(), optional(2), optional(3)),
+      }
+      .with_type(entry_type)
+    };
+    Ok(
+      days
+        .into_iter()
+        .flat_map(|(date, last_date)| match time {
+          Some(time) => date
+            .iter_days()
+            .take_while(|date| *date <= last_date)
+            .map(|date| schedule_entry(date, time))
+            .collect(),
+          None => vec![schedule_entry(date, (NaiveTime::MIN, None)).all_day(last_date)],
+        })
+        .collect(),
+    )
+  }
+
+  /// `Gretchen | Anna / Berta | Sz 1, 3 | Sz 5` with the scenes played
+  /// silently in the last field.
+  fn scene_entry(&self, line: Field) -> Result<SceneEntry, SceneSchedulerError> {
+    let fields = line.split(&[FIELD_SEPARATOR], 4);
+    let role = fields[0];
+    if role.text.is_empty() {
+      return Err(self.error(role.column, Phrase::WrongRole, role.text));
+    }
+    let cast = match fields.get(1) {
+      Some(cast) if !cast.text.is_empty() => *cast,
+      _ => return Err(self.error(line.end(), Phrase::PlanMissingField, "")),
+    };
+    let scenes = |index: usize| match fields.get(index) {
+      Some(field) => scene_list(field.text)
+        .ok_or_else(|| self.error(field.column, Phrase::PlanWrongScenes, field.text)),
+      None => Ok(vec![]),
+    };
+    let played = scenes(2)?;
+    let silent = scenes(3)?;
+    let silent_play = played
+      .iter()
+      .map(|_| false)
+      .chain(silent.iter().map(|_| true))
+      .collect();
+    let mut cast = cast
+      .text
+      .split(CAST_SEPARATOR)
+      .map(|person| person.trim().to_owned())
+      .filter(|person| !person.is_empty());
+    let mut scene_entry = SceneEntry::new(
+      role.text.to_owned(),
+      cast.next().unwrap_or_default(),
+      played.into_iter().chain(silent).collect(),
+      silent_play,
+    );
+    scene_entry.alternates = cast.collect();
+    Ok(scene_entry)
+  }
+
+  /// `Chor | Anna, Bert, Carla`
+  fn group(&self, line: Field) -> Result<Group, SceneSchedulerError> {
+    let fields = line.split(&[FIELD_SEPARATOR], 2);
+    let name = fields[0];
+    if name.text.is_empty() {
+      return Err(self.error(name.column, Phrase::WrongGroupName, name.text));
+    }
+    let members = match fields.get(1) {
+      Some(members) if !members.text.is_empty() => members
+        .text
+        .split(LIST_SEPARATORS[0])
+        .map(|member| member.trim().to_owned())
+        .filter(|member| !member.is_empty())
+        .collect(),
+      _ => return Err(self.error(line.end(), Phrase::PlanMissingField, "")),
+    };
+    Ok(Group {
+      name: name.text.to_owned(),
+      members,
+    })
+  }
+
+  /// `Saal | Hauptstrasse 1, Zürich | Altbau | 2. OG | Treppe links | 47.37, 8.54`
+  /// with everything after the room being optional.
+  fn venue(&self, line: Field) -> Result<Venue, SceneSchedulerError> {
+    let fields = line.split(&[FIELD_SEPARATOR], 6);
+    let room = fields[0];
+    if room.text.is_empty() {
+      return Err(self.error(room.column, Phrase::WrongRoomName, room.text));
+    }
+    let optional = |index: usize| {
+      fields
+        .get(index)
+        .filter(|field| !field.text.is_empty())
+        .map(|field| field.text.to_owned())
+    };
+    let coordinates = match fields.get(5) {
+      Some(coordinates) if !coordinates.text.is_empty() => {
+        let degrees = coordinates
+          .split(&[LIST_SEPARATORS[0]], 2)
+          .iter()
+          .map(|degrees| degrees.text.parse::<f64>().ok())
+          .collect::<Vec<_>>();
+        match degrees[..] {
+          [Some(latitude), Some(longitude)] => Some((latitude, longitude)),
+          _ => {
+            return Err(self.error(
+              coordinates.column,
+              Phrase::WrongCoordinates,
+              coordinates.text,
+            ))
+          }
+        }
+      }
+      _ => None,
+    };
+    Ok(Venue {
+      room: room.text.to_owned(),
+      address: optional(1),
+      building: optional(2),
+      floor: optional(3),
+      directions: optional(4),
+      coordinates,
+    })
+  }
+}
+
+/// The scenes of a list like `Sz 3, 5` or `3/5`, `None` if the text is not
+/// a list of scenes.
+fn scene_list(text: &str) -> Option<Vec<String>> {
+  let list = match text.strip_prefix(SCENES_PREFIX) {
+    Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest,
+    _ if text.is_empty() || text.starts_with(|c: char| c.is_ascii_digit()) => text,
+    _ => return None,
+  };
+  Some(
+    list
+      .split(&LIST_SEPARATORS[..])
+      .map(|scene| scene.trim().to_owned())
+      .filter(|scene| !scene.is_empty())
+      .collect(),
+  )
+}
+
+/// The text with the escaping `\` removed.
+fn unescape(text: &str) -> String {
+  let mut unescaped = String::with_capacity(text.len());
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      ESCAPE => unescaped.push(chars.next().unwrap_or(ESCAPE)),
+      c => unescaped.push(c),
+    }
+  }
+  unescaped
+}
+
+/// The text with `\` in front of `special` characters and itself.
+fn escape(text: &str, special: &[char]) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    if c == ESCAPE || special.contains(&c) {
+      escaped.push(ESCAPE);
+    }
+    escaped.push(c);
+  }
+  escaped
+}
+
+/// Reads a plan written in the text format, e.g.
+///
+/// ```text
+/// location: Theater
+/// silent play from: 2024-03-01
+///
+/// [schedule]
+/// 2024-03-05 18:00-21:00 | Sz 3, 5 | Saal | Mit Kostüm
+/// 2024-03-06 19:00 | Hauptprobe
+/// 2024-03-07 19:00 | Hauptprobe: Sz 1, 2
+///
+/// [cast]
+/// Gretchen | Anna / Berta | Sz 3 | Sz 5
+/// Chor | Chor | Sz 5
+///
+/// [groups]
+/// Chor | Carla, Dora
+///
+/// [venues]
+/// Saal | Hauptstrasse 1, Zürich | Altbau | 2. OG | Treppe links | 47.37, 8.54
+/// ```
+///
+/// Lines starting with `#` are comments. The types of the entries are
+/// recognized by the names of `entry_categories`.
+pub fn parse_plan(
+  content: &str,
+  file: &str,
+  entry_categories: &[EntryCategory],
+) -> Result<Model, SceneSchedulerError> {
+  let mut section = Section::Settings;
+  let mut location = None;
+  let mut mandatory_silent_play = None;
+  let mut schedule_entries = vec![];
+  let mut scene_entries = vec![];
+  let mut groups = vec![];
+  let mut venues = vec![];
+  // Where the location was expected at the latest, after the last line if
+  // there are no sections.
+  let mut settings_end = (content.lines().count() + 1, 1, String::new());
+  for (index, text) in content.lines().enumerate() {
+    let parser = Parser {
+      file,
+      line: index + 1,
+      entry_categories,
+    };
+    let line = Field::new(text).split(&[], 1)[0];
+    if line.text.is_empty() || line.text.starts_with(COMMENT) {
+      continue;
+    }
+    if let Some(name) = line
+      .text
+      .strip_prefix('[')
+      .and_then(|name| name.strip_suffix(']'))
+    {
+      if section == Section::Settings {
+        settings_end = (index + 1, line.column, line.text.to_owned());
+      }
+      section = match name.trim() {
+        SCHEDULE_SECTION => Section::Schedule,
+        CAST_SECTION => Section::Cast,
+        GROUPS_SECTION => Section::Groups,
+        VENUES_SECTION => Section::Venues,
+        _ => return Err(parser.error(line.column, Phrase::PlanWrongSection, line.text)),
+      };
+      continue;
+    }
+    match section {
+      Section::Settings => {
+        let setting = line.split(&[SETTING_SEPARATOR], 2);
+        let value = match setting.get(1) {
+          Some(value) => *value,
+          None => return Err(parser.error(line.column, Phrase::PlanWrongSetting, line.text)),
+        };
+        match setting[0].text {
+          LOCATION_SETTING => location = Some(value.text.to_owned()),
+          SILENT_PLAY_SETTING => mandatory_silent_play = Some(parser.date(value)?),
+          key => return Err(parser.error(setting[0].column, Phrase::PlanWrongSetting, key)),
+        }
+      }
+      Section::Schedule => schedule_entries.extend(parser.schedule_entries(line)?),
+      Section::Cast => scene_entries.push(parser.scene_entry(line)?),
+      Section::Groups => groups.push(parser.group(line)?),
+      Section::Venues => venues.push(parser.venue(line)?),
+    }
+  }
+  let location = location
+    .filter(|location| !location.is_empty())
+    .ok_or_else(|| {
+      let (line, column, token) = settings_end;
+      Parser {
+        file,
+        line,
+        entry_categories,
+      }
+      .error(column, Phrase::LocationMissing, &token)
+    })?;
+  Ok(Model {
+    schedule_entries,
+    scene_entries,
+    groups,
+    people: vec![],
+    crew: vec![],
+    venues,
+    location,
+    mandatory_silent_play,
+  })
+}
+
+/// Writes the plan in the canonical text format. People and crew are not
+/// part of it, they are taken from the config. Types which are not
+/// recognized from the scenes by `entry_categories` are written in front of
+/// them.
+pub fn format_plan(model: &Model, entry_categories: &[EntryCategory]) -> String {
+  let mut lines = vec![format!("{}: {}", LOCATION_SETTING, model.location)];
+  if let Some(date) = model.mandatory_silent_play {
+    lines.push(format!(
+      "{}: {}",
+      SILENT_PLAY_SETTING,
+      date.format(DATE_FORMAT)
+    ));
+  }
+
+  lines.push(String::new());
+  lines.push(format!("[{}]", SCHEDULE_SECTION));
+  for schedule_entry in &model.schedule_entries {
+    let mut when = schedule_entry.date.format(DATE_FORMAT).to_string();
+    match (schedule_entry.all_day_until, schedule_entry.start_stop_time) {
+      (Some(last_date), _) if last_date > schedule_entry.date => when.push_str(&format!(
+        "{}{}",
+        DATE_RANGE_SEPARATOR,
+        last_date.format(DATE_FORMAT)
+      )),
+      (Some(_), _) => (),
+      (None, (start, stop)) => {
+        when.push_str(&format!(" {}", start.format(TIME_FORMAT)));
+        // Inferred stop times are inferred again when the plan is read.
+        if let (Some(stop), false) = (stop, schedule_entry.stop_time_inferred) {
+          when.push_str(&format!(
+            "{}{}",
+            TIME_SEPARATORS[0],
+            stop.format(TIME_FORMAT)
+          ));
+        }
+      }
+    }
+    let session_separator = format!("{} ", SESSION_SEPARATOR);
+    let room = |room: Option<&str>| {
+      escape(
+        room.unwrap_or_default(),
+        &[FIELD_SEPARATOR, SESSION_SEPARATOR],
+      )
+    };
+    let (scenes, room) = match &schedule_entry.scenes {
+      _ if !schedule_entry.sessions.is_empty() => (
+        schedule_entry
+          .sessions
+          .iter()
+          .map(|session| format_scene_list(&session.scenes))
+          .collect::<Vec<_>>()
+          .join(&session_separator),
+        schedule_entry
+          .sessions
+          .iter()
+          .map(|session| room(session.room.as_deref()))
+          .collect::<Vec<_>>()
+          .join(&session_separator)
+          .trim_end_matches(&[SESSION_SEPARATOR, ' '][..])
+          .to_owned(),
+      ),
+      Scenes::Normal(scenes) => (
+        format_scene_list(scenes),
+        room(schedule_entry.room.as_deref()),
+      ),
+      Scenes::Special(name) => {
+        let mut name = escape(
+          name,
+          &[FIELD_SEPARATOR, SESSION_SEPARATOR, SETTING_SEPARATOR],
+        );
+        // Otherwise it would be read as a list of scenes.
+        if scene_list(&name).is_some() && !name.is_empty() {
+          name.insert(0, ESCAPE);
+        }
+        (name, room(schedule_entry.room.as_deref()))
+      }
+    };
+    let recognized = parse_entry_type(&schedule_entry.scenes, None, entry_categories);
+    let scenes = match recognized {
+      Ok(entry_type) if entry_type == schedule_entry.entry_type => scenes,
+      _ => format!(
+        "{}{} {}",
+        schedule_entry.entry_type.keyword(),
+        SETTING_SEPARATOR,
+        scenes
+      )
+      .trim_end()
+      .to_owned(),
+    };
+    lines.push(format_fields(vec![
+      when,
+      scenes,
+      room,
+      // Notes have to stay on their line.
+      escape(
+        &schedule_entry
+          .note
+          .as_deref()
+          .unwrap_or_default()
+          .lines()
+          .map(str::trim)
+          .collect::<Vec<_>>()
+          .join(" "),
+        &[],
+      ),
+    ]));
+  }
+
+  if !model.scene_entries.is_empty() {
+    lines.push(String::new());
+    lines.push(format!("[{}]", CAST_SECTION));
+  }
+  for scene_entry in &model.scene_entries {
+    let (played, silent): (Vec<_>, Vec<_>) = scene_entry
+      .scenes
+      .iter()
+      .zip(&scene_entry.silent_play)
+      .partition(|(_, silent_play)| !**silent_play);
+    let scenes = |scenes: Vec<(&String, &bool)>| {
+      scenes
+        .into_iter()
+        .map(|(scene, _)| scene.clone())
+        .collect::<Vec<_>>()
+    };
+    let mut fields = vec![
+      scene_entry.role.clone(),
+      scene_entry
+        .cast()
+        .iter()
+        .map(|person| person.as_str())
+        .collect::<Vec<_>>()
+        .join(&format!(" {} ", CAST_SEPARATOR)),
+      format_scene_list(&scenes(played)),
+    ];
+    if !silent.is_empty() {
+      fields.push(format_scene_list(&scenes(silent)));
+    }
+    lines.push(format_fields(fields));
+  }
+
+  if !model.groups.is_empty() {
+    lines.push(String::new());
+    lines.push(format!("[{}]", GROUPS_SECTION));
+  }
+  for group in &model.groups {
+    lines.push(format_fields(vec![
+      group.name.clone(),
+      group.members.join(&format!("{} ", LIST_SEPARATORS[0])),
+    ]));
+  }
+
+  if !model.venues.is_empty() {
+    lines.push(String::new());
+    lines.push(format!("[{}]", VENUES_SECTION));
+  }
+  for venue in &model.venues {
+    lines.push(format_fields(vec![
+      venue.room.clone(),
+      venue.address.clone().unwrap_or_default(),
+      venue.building.clone().unwrap_or_default(),
+      venue.floor.clone().unwrap_or_default(),
+      venue.directions.clone().unwrap_or_default(),
+      venue
+        .coordinates
+        .map(|(latitude, longitude)| format!("{}{} {}", latitude, LIST_SEPARATORS[0], longitude))
+        .unwrap_or_default(),
+    ]));
+  }
+  lines.push(String::new());
+  lines.join("\n")
+}
+
+fn format_scene_list(scenes: &[String]) -> String {
+  if scenes.is_empty() {
+    String::new()
+  } else {
+    format!("{} {}", SCENES_PREFIX, scenes.join(", "))
+  }
+}
+
+/// Joins the fields of a line, leaving out empty fields at the end.
+fn format_fields(mut fields: Vec<String>) -> String {
+  while fields.len() > 1 && matches!(fields.last(), Some(field) if field.is_empty()) {
+    fields.pop();
+  }
+  let mut line = fields[0].clone();
+  for field in &fields[1..] {
+    line.push(' ');
+    line.push(FIELD_SEPARATOR);
+    if !field.is_empty() {
+      line.push(' ');
+      line.push_str(field);
+    }
+  }
+  line
+}
+
+/// Whether the input file is written in the text format.
+pub fn is_plan_file(path: &str) -> bool {
+  match std::path::Path::new(path).extension() {
+    Some(extension) => extension.eq_ignore_ascii_case(PLAN_EXTENSION),
+    None => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::structures::EntryType;
+
+  fn parse(plan: &str) -> Result<Model, SceneSchedulerError> {
+    parse_plan(plan, "faust.plan", &EntryCategory::defaults())
+  }
+
+  fn format(model: &Model) -> String {
+    format_plan(model, &EntryCategory::defaults())
+  }
+
+  const PLAN: &str = "# Faust
+location:   Theater
+silent play from: 2024-03-01
+
+[schedule]
+2024-03-05 18:00 |  3,5|Saal | Mit Kostüm
+2024-03-05 20:00-22:00 | Sz 6
+2024-03-06 19:00 | Hauptprobe
+
+[cast]
+Gretchen | Anna/Berta | Sz 3 | Sz 5
+Chor | Chor | 6
+
+[groups]
+Chor | Carla,Dora
+
+[venues]
+Saal|Hauptstrasse 1, Zürich||1. OG|| 47.37,8.54
+";
+
+  const CANONICAL: &str = "location: Theater
+silent play from: 2024-03-01
+
+[schedule]
+2024-03-05 18:00 | Sz 3, 5 | Saal | Mit Kostüm
+2024-03-05 20:00-22:00 | Sz 6
+2024-03-06 19:00 | Hauptprobe
+
+[cast]
+Gretchen | Anna / Berta | Sz 3 | Sz 5
+Chor | Chor | Sz 6
+
+[groups]
+Chor | Carla, Dora
+
+[venues]
+Saal | Hauptstrasse 1, Zürich | | 1. OG | | 47.37, 8.54
+";
+
+  #[test]
+  fn test_parse_plan() {
+    let model = parse(PLAN).unwrap();
+    assert_eq!(model.location, "Theater");
+    assert_eq!(model.schedule_entries.len(), 3);
+    let first = &model.schedule_entries[0];
+    assert_eq!(
+      first.scenes,
+      Scenes::Normal(vec!["3".to_string(), "5".to_string()])
+    );
+    assert_eq!(first.room.as_deref(), Some("Saal"));
+    assert_eq!(first.note.as_deref(), Some("Mit Kostüm"));
+    assert_eq!(
+      model.schedule_entries[2].scenes,
+      Scenes::Special("Hauptprobe".to_string())
+    );
+    let gretchen = &model.scene_entries[0];
+    assert_eq!(gretchen.cast(), vec!["Anna", "Berta"]);
+    assert_eq!(gretchen.is_scene_silent_play(&"5".to_string()), Some(true));
+    assert_eq!(model.groups[0].members, vec!["Carla", "Dora"]);
+    let saal = &model.venues[0];
+    assert_eq!(saal.location(), "Saal, 1. OG, Hauptstrasse 1, Zürich");
+    assert_eq!(saal.coordinates, Some((47.37, 8.54)));
+  }
+
+  #[test]
+  fn test_format_plan_is_canonical() {
+    let formatted = format(&parse(PLAN).unwrap());
+    assert_eq!(formatted, CANONICAL);
+    assert_eq!(format(&parse(&formatted).unwrap()), CANONICAL);
+
+    let escaped = "location: Theater\n\n[schedule]\n\
+                   2024-03-05 18:00 | \\2. Leseprobe | Saal\\; Foyer\n\
+                   2024-03-06 18:00 | \\Sz 3\\; Technik\\: Licht | Raum \\| B | C:\\\\\n";
+    let model = parse(escaped).unwrap();
+    let first = &model.schedule_entries[0];
+    assert_eq!(first.scenes, Scenes::Special("2. Leseprobe".to_string()));
+    assert_eq!(first.room.as_deref(), Some("Saal; Foyer"));
+    let second = &model.schedule_entries[1];
+    assert_eq!(
+      second.scenes,
+      Scenes::Special("Sz 3; Technik: Licht".to_string())
+    );
+    assert_eq!(second.room.as_deref(), Some("Raum | B"));
+    assert_eq!(second.note.as_deref(), Some("C:\\"));
+    assert_eq!(format(&model), escaped);
+  }
+
+  #[test]
+  fn test_parse_plan_reports_line_and_column() {
+    let plan = "location: Theater\n[schedule]\n2024-03-05 18:00 | Sz 1\n2024-03-06 7pm | Sz 2\n";
+    match parse(plan).unwrap_err() {
+      SceneSchedulerError::PlanParseError {
+        line,
+        column,
+        expected,
+        token,
+        ..
+      } => {
+        assert_eq!((line, column), (4, 12));
+        assert_eq!(expected, Phrase::WrongTimeFormat);
+        assert_eq!(token, "7pm");
+      }
+      error => panic!("unexpected error {:?}", error),
+    }
+  }
+
+  #[test]
+  fn test_parse_plan_counts_columns_in_characters() {
+    let error = |plan: &str| match parse(plan).unwrap_err() {
+      SceneSchedulerError::PlanParseError {
+        line,
+        column,
+        expected,
+        token,
+        ..
+      } => (line, column, expected, token),
+      error => panic!("unexpected error {:?}", error),
+    };
+    let model = parse("location: Theater\n[schedule]\n2024-03-05 18:00–21:00 | Sz 3\n").unwrap();
+    assert_eq!(
+      model.schedule_entries[0].start_stop_time.1,
+      NaiveTime::from_hms_opt(21, 0, 0)
+    );
+    assert_eq!(
+      error("location: Theater\n[schedule]\n2024-03-05 18:00–2x:00 | Sz 3\n"),
+      (3, 18, Phrase::WrongTimeFormat, "2x:00".to_string())
+    );
+    assert_eq!(
+      error(
+        "location: Theater\n[schedule]\n2024-03-05 18:00 | Sz 1; Sz 2 | Bühne; Foyer; Zürich\n"
+      ),
+      (3, 47, Phrase::WrongSessionRooms, "Zürich".to_string())
+    );
+    assert_eq!(
+      error("location: Theater\n[cast]\nGretchen | Jürgen | Sz 3 | Szene 5\n"),
+      (3, 28, Phrase::PlanWrongScenes, "Szene 5".to_string())
+    );
+    assert_eq!(
+      error("location: Theater\n[venues]\nSaal | Hauptstrasse 1, Zürich | | | | 47.37; 8.54\n"),
+      (3, 39, Phrase::WrongCoordinates, "47.37; 8.54".to_string())
+    );
+  }
+
+  #[test]
+  fn test_missing_location() {
+    let error = |plan: &str| match parse(plan).unwrap_err() {
+      SceneSchedulerError::PlanParseError {
+        line,
+        column,
+        expected: Phrase::LocationMissing,
+        ..
+      } => (line, column),
+      error => panic!("unexpected error {:?}", error),
+    };
+    assert_eq!(
+      error("# Faust\nsilent play from: 2024-03-01\n\n  [schedule]\n2024-03-05 | Sz 1\n"),
+      (4, 3)
+    );
+    assert_eq!(error("# Faust\nsilent play from: 2024-03-01\n"), (3, 1));
+    assert_eq!(error(""), (1, 1));
+  }
+
+  #[test]
+  fn test_parallel_sessions() {
+    let plan = "location: Theater\n[schedule]\n2024-03-05 18:00 | Sz 3, 4; Sz 7 | Saal; Raum B\n";
+    let model = parse(plan).unwrap();
+    let schedule_entry = &model.schedule_entries[0];
+    assert_eq!(
+      schedule_entry.scenes,
+      Scenes::Normal(vec!["3".to_string(), "4".to_string(), "7".to_string()])
+    );
+    let chor = SceneEntry::new(
+      "Chor".to_string(),
+      "Carla".to_string(),
+      vec!["7".to_string()],
+      vec![false],
+    );
+    assert_eq!(schedule_entry.room_for(Some(&chor)), Some("Raum B"));
+    assert_eq!(schedule_entry.rooms("Theater"), vec!["Saal", "Raum B"]);
+    assert!(format(&model).contains("2024-03-05 18:00 | Sz 3, 4; Sz 7 | Saal; Raum B\n"));
+
+    let error =
+      parse("location: Theater\n[schedule]\n2024-03-05 18:00 | Sz 3; Probe\n").unwrap_err();
+    assert!(matches!(
+      error,
+      SceneSchedulerError::PlanParseError {
+        column: 26,
+        expected: Phrase::WrongSessionScenes,
+        ..
+      }
+    ));
+  }
+
+  #[test]
+  fn test_all_day_entries() {
+    let plan = "location: Theater\n[schedule]\n2024-04-12..2024-04-14 | Gastspiel\n\
+                2024-04-15 | Sz 1\n2024-04-16..2024-04-17 10:00-12:00 | Sz 2\n";
+    let model = parse(plan).unwrap();
+    let dates = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+    let tour = &model.schedule_entries[0];
+    assert!(tour.is_all_day());
+    assert_eq!(
+      tour.dates().collect::<Vec<_>>(),
+      vec![dates(12), dates(13), dates(14)]
+    );
+    assert_eq!(model.schedule_entries[1].last_date(), dates(15));
+    assert_eq!(model.schedule_entries.len(), 4);
+    assert!(!model.schedule_entries[3].is_all_day());
+    assert_eq!(model.schedule_entries[3].date, dates(17));
+    assert!(format(&model).contains(
+      "2024-04-12..2024-04-14 | Gastspiel\n2024-04-15 | Sz 1\n2024-04-16 10:00-12:00 | Sz 2\n"
+    ));
+
+    let error =
+      parse("location: Theater\n[schedule]\n2024-04-14..2024-04-12 | Gastspiel\n").unwrap_err();
+    assert!(matches!(
+      error,
+      SceneSchedulerError::PlanParseError {
+        column: 13,
+        expected: Phrase::PlanWrongDate,
+        ..
+      }
+    ));
+  }
+
+  #[test]
+  fn test_recurring_entries() {
+    let plan = "location: Theater\n[schedule]\n\
+                2024-03-05 19:00-22:00 weekly until 2024-03-26 except 2024-03-12 | Sz 1\n";
+    let model = parse(plan).unwrap();
+    assert_eq!(
+      model
+        .schedule_entries
+        .iter()
+        .map(|schedule_entry| schedule_entry.date.format(DATE_FORMAT).to_string())
+        .collect::<Vec<_>>(),
+      vec!["2024-03-05", "2024-03-19", "2024-03-26"]
+    );
+
+    let error =
+      parse("location: Theater\n[schedule]\n2024-03-05 19:00 weekly until 2024-03-01 | Sz 1\n")
+        .unwrap_err();
+    assert!(matches!(
+      error,
+      SceneSchedulerError::PlanParseError {
+        column: 18,
+        expected: Phrase::WrongRecurrence,
+        ..
+      }
+    ));
+  }
+
+  #[test]
+  fn test_entry_types() {
+    let plan = "location: Theater\n[schedule]\n2024-03-05 19:00 | Hauptprobe\n\
+                2024-03-06 19:00 | HP: Sz 1, 2 | Saal\n2024-03-07 10:00 | Sitzung: Technik\n";
+    let model = parse(plan).unwrap();
+    let entry_types = model
+      .schedule_entries
+      .iter()
+      .map(|schedule_entry| schedule_entry.entry_type)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      entry_types,
+      vec![
+        EntryType::DressRehearsal,
+        EntryType::DressRehearsal,
+        EntryType::Meeting
+      ]
+    );
+    assert_eq!(
+      model.schedule_entries[1].scenes,
+      Scenes::Normal(vec!["1".to_string(), "2".to_string()])
+    );
+    assert!(format(&model).contains(
+      "2024-03-05 19:00 | Hauptprobe\n\
+       2024-03-06 19:00 | dress rehearsal: Sz 1, 2 | Saal\n\
+       2024-03-07 10:00 | meeting: Technik\n"
+    ));
+  }
+}
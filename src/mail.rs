@@ -0,0 +1,341 @@
+use chrono::NaiveDate;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, EMAIL_DIR};
+use crate::i18n::{fill, Language, Phrase};
+use crate::ics::{create_person_calendar, Participants};
+use crate::output::{sanitize_file_name, OutputFiles};
+use crate::sorting::find_person_info;
+use crate::structures::{
+  Person, PersonInfo, PersonToSceneAndScheduleEntry, SceneEntry, SceneSchedulerError, ScheduleEntry,
+};
+use crate::template::format_call;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MailConfig {
+  /// Sender of the messages, e.g. `Anna Müller <anna@example.com>`. If not
+  /// set, the production manager is used.
+  #[serde(default)]
+  pub from: Option<String>,
+  /// Templates for subject and body. Supports `{production}`, `{person}` and
+  /// in the body `{calls}`.
+  #[serde(default)]
+  pub subject: Option<String>,
+  #[serde(default)]
+  pub body: Option<String>,
+  /// If set, the messages are also sent with this server.
+  #[serde(default)]
+  pub smtp: Option<SmtpConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpConfig {
+  pub host: String,
+  #[serde(default)]
+  pub port: Option<u16>,
+  #[serde(default)]
+  pub username: Option<String>,
+  #[serde(default)]
+  pub password: Option<String>,
+  #[serde(default)]
+  pub security: SmtpSecurity,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum SmtpSecurity {
+  /// Plain text, only meant for local test servers.
+  None,
+  #[default]
+  StartTls,
+  Tls,
+}
+
+/// Writes one `.eml` file per person with an email address. The message lists
+/// the upcoming calls and has the calendar of the person attached. With an
+/// SMTP server configured, the messages are sent as well. Returns the people
+/// who were skipped because they have no email address.
+#[allow(clippy::too_many_arguments)]
+pub fn write_emails(
+  person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+  people: &[PersonInfo],
+  participants: &Participants,
+  output_files: &mut OutputFiles,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+  today: NaiveDate,
+) -> Result<Vec<Person>, SceneSchedulerError> {
+  let from = sender(config, people)?;
+  let mut messages = vec![];
+  let mut without_email = vec![];
+  for (person, schedule_to_scene_entries) in person_to_scene_and_schedule_entry {
+    let to = match find_person_info(person, people).and_then(|info| info.email.as_ref()) {
+      Some(email) => mailbox(person, email)?,
+      None => {
+        without_email.push(person.clone());
+        continue;
+      }
+    };
+    let calendar = create_person_calendar(
+      person,
+      schedule_to_scene_entries,
+      participants,
+      config,
+      default_location,
+      language,
+    )?;
+    let message = create_message(
+      from.clone(),
+      to,
+      person,
+      &upcoming_calls(schedule_to_scene_entries, default_location, today),
+      calendar.to_string(),
+      config,
+      language,
+    )?;
+    std::fs::write(
+      output_files.file(Some(EMAIL_DIR), person, "eml")?,
+      message.formatted(),
+    )?;
+    messages.push(message);
+  }
+  if let Some(smtp) = &config.mail.smtp {
+    send_messages(&messages, smtp)?;
+  }
+  Ok(without_email)
+}
+
+/// The calls which are not over yet, including entries of several days which
+/// have already started.
+fn upcoming_calls(
+  schedule_to_scene_entries: &[&(&ScheduleEntry, Option<&SceneEntry>)],
+  default_location: &str,
+  today: NaiveDate,
+) -> Vec<String> {
+  schedule_to_scene_entries
+    .iter()
+    .filter(|(schedule_entry, _)| schedule_entry.last_date() >= today)
+    .map(|(schedule_entry, scene_entry)| {
+      format_call(schedule_entry, *scene_entry, default_location)
+    })
+    .collect()
+}
+
+fn sender(config: &Config, people: &[PersonInfo]) -> Result<Mailbox, SceneSchedulerError> {
+  if let Some(from) = &config.mail.from {
+    return from
+      .parse()
+      .map_err(|e| mail_error(Phrase::MailInvalidSender, from, e));
+  }
+  let production_manager = config
+    .production_manager
+    .as_deref()
+    .and_then(|name| find_person_info(name, people));
+  match production_manager.and_then(|info| info.email.as_ref().map(|email| (&info.name, email))) {
+    Some((name, email)) => mailbox(name, email),
+    None => Err(mail_error(Phrase::MailNoSender, "", "")),
+  }
+}
+
+fn mailbox(name: &Person, email: &str) -> Result<Mailbox, SceneSchedulerError> {
+  let address = email
+    .parse()
+    .map_err(|e| mail_error(Phrase::MailInvalidAddress, email, e))?;
+  Ok(Mailbox::new(Some(name.clone()), address))
+}
+
+fn create_message(
+  from: Mailbox,
+  to: Mailbox,
+  person: &Person,
+  calls: &[String],
+  calendar: String,
+  config: &Config,
+  language: Language,
+) -> Result<Message, SceneSchedulerError> {
+  let calls = if calls.is_empty() {
+    language.tr(Phrase::MailNoCalls).to_owned()
+  } else {
+    calls
+      .iter()
+      .map(|call| format!("- {}", call))
+      .collect::<Vec<_>>()
+      .join("\n")
+  };
+  let values: [(&str, &dyn std::fmt::Display); 3] = [
+    ("production", &config.production_name),
+    ("person", person),
+    ("calls", &calls),
+  ];
+  let subject = fill(
+    config
+      .mail
+      .subject
+      .as_deref()
+      .unwrap_or_else(|| language.tr(Phrase::MailSubject)),
+    &values,
+  );
+  let body = fill(
+    config
+      .mail
+      .body
+      .as_deref()
+      .unwrap_or_else(|| language.tr(Phrase::MailBody)),
+    &values,
+  );
+  let calendar_type = ContentType::parse("text/calendar; charset=utf-8; method=PUBLISH")
+    .map_err(|e| mail_error(Phrase::MailNotCreated, person, e))?;
+  Message::builder()
+    .from(from)
+    .to(to)
+    .subject(subject)
+    .multipart(
+      MultiPart::mixed()
+        .singlepart(SinglePart::plain(body))
+        .singlepart(
+          Attachment::new(format!("{}.ics", sanitize_file_name(person)))
+            .body(calendar, calendar_type),
+        ),
+    )
+    .map_err(|e| mail_error(Phrase::MailNotCreated, person, e))
+}
+
+fn mail_error(message: Phrase, value: &str, error: impl std::fmt::Display) -> SceneSchedulerError {
+  SceneSchedulerError::Mail {
+    message,
+    value: value.to_owned(),
+    error: error.to_string(),
+  }
+}
+
+fn send_messages(messages: &[Message], smtp: &SmtpConfig) -> Result<(), SceneSchedulerError> {
+  let smtp_error =
+    |e: lettre::transport::smtp::Error| mail_error(Phrase::MailNotSent, &smtp.host, e);
+  let mut builder = match smtp.security {
+    SmtpSecurity::None => SmtpTransport::builder_dangerous(&smtp.host),
+    SmtpSecurity::StartTls => SmtpTransport::starttls_relay(&smtp.host).map_err(smtp_error)?,
+    SmtpSecurity::Tls => SmtpTransport::relay(&smtp.host).map_err(smtp_error)?,
+  };
+  if let Some(port) = smtp.port {
+    builder = builder.port(port);
+  }
+  if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+    builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+  }
+  let transport = builder.build();
+  for message in messages {
+    transport.send(message).map_err(smtp_error)?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{BufRead, BufReader, Write};
+  use std::net::TcpListener;
+
+  /// Accepts one connection, answers every command with success and returns
+  /// the received message data.
+  fn stand_in_smtp_server() -> (u16, std::thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = std::thread::spawn(move || {
+      let (stream, _) = listener.accept().unwrap();
+      let mut writer = stream.try_clone().unwrap();
+      let mut reader = BufReader::new(stream);
+      writer.write_all(b"220 localhost\r\n").unwrap();
+      let mut data = String::new();
+      let mut in_data = false;
+      let mut line = String::new();
+      while reader.read_line(&mut line).unwrap() > 0 {
+        if in_data {
+          if line == ".\r\n" {
+            in_data = false;
+            writer.write_all(b"250 OK\r\n").unwrap();
+          } else {
+            data.push_str(&line);
+          }
+        } else if line.starts_with("DATA") {
+          in_data = true;
+          writer.write_all(b"354 Go ahead\r\n").unwrap();
+        } else if line.starts_with("QUIT") {
+          writer.write_all(b"221 Bye\r\n").unwrap();
+          break;
+        } else {
+          writer.write_all(b"250 OK\r\n").unwrap();
+        }
+        line.clear();
+      }
+      data
+    });
+    (port, handle)
+  }
+
+  #[test]
+  fn test_upcoming_calls() {
+    use crate::structures::Scenes;
+    let date = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+    let entry = |day, last_day, scene: &str| {
+      ScheduleEntry::new(
+        date(day),
+        (chrono::NaiveTime::MIN, None),
+        Scenes::Special(scene.to_string()),
+        None,
+        None,
+      )
+      .all_day(date(last_day))
+    };
+    let (over, running, next) = (
+      entry(10, 11, "Aufbau"),
+      entry(11, 14, "Gastspiel"),
+      entry(15, 15, "Abbau"),
+    );
+    let calls = [(&over, None), (&running, None), (&next, None)];
+    assert_eq!(
+      upcoming_calls(&calls.iter().collect::<Vec<_>>(), "Theater", date(12)),
+      vec![
+        "11.04.2024–14.04.2024, Theater: Gastspiel",
+        "15.04.2024, Theater: Abbau"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_send_message_with_calendar() {
+    let config = Config {
+      production_name: "Faust".to_string(),
+      ..Config::default()
+    };
+    let from = mailbox(&"Regie".to_string(), "regie@example.com").unwrap();
+    let to = mailbox(&"Anna".to_string(), "anna@example.com").unwrap();
+    let message = create_message(
+      from,
+      to,
+      &"Anna".to_string(),
+      &["01.05.2022 10:00, Probebühne: 3 (Gretchen)".to_string()],
+      "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n".to_string(),
+      &config,
+      Language::English,
+    )
+    .unwrap();
+
+    let (port, server) = stand_in_smtp_server();
+    let smtp = SmtpConfig {
+      host: "127.0.0.1".to_string(),
+      port: Some(port),
+      username: None,
+      password: None,
+      security: SmtpSecurity::None,
+    };
+    send_messages(&[message], &smtp).unwrap();
+    let data = server.join().unwrap();
+    assert!(data.contains("Subject: Faust: rehearsal schedule for Anna"));
+    assert!(data.contains("Content-Type: text/calendar"));
+    assert!(data.contains("Anna.ics"));
+  }
+}
@@ -1,16 +1,77 @@
+mod attendance;
+mod caldav;
 mod config;
 mod gui;
+mod html;
+mod i18n;
 mod ics;
 mod io;
+mod mail;
+mod model;
+mod output;
+mod pdf;
 mod scheduler;
+mod server;
 mod sorting;
 mod structures;
+mod template;
 
-use gui::Gui;
+use config::Config;
+use gui::{Gui, GuiConfig};
 use iced::{Sandbox, Settings};
+use scheduler::Scheduler;
 use structures::SceneSchedulerError;
 
 fn main() -> Result<(), SceneSchedulerError> {
+  let mut args = std::env::args().skip(1);
+  match args.next().as_deref() {
+    Some("serve") => {
+      let language = GuiConfig::load().map(|c| c.language).unwrap_or_default();
+      return server::serve(Config::load()?, language);
+    }
+    // Writes the parsed model to the given file or to stdout.
+    Some("export-json") => {
+      let json = Scheduler {
+        config: Config::load()?,
+      }
+      .model_json()?;
+      match args.next() {
+        Some(path) => std::fs::write(path, json)?,
+        None => println!("{}", json),
+      }
+      return Ok(());
+    }
+    // Writes the model as plain text plan to the given file or to stdout.
+    Some("export-plan") => {
+      let plan = Scheduler {
+        config: Config::load()?,
+      }
+      .model_plan()?;
+      match args.next() {
+        Some(path) => std::fs::write(path, plan)?,
+        None => print!("{}", plan),
+      }
+      return Ok(());
+    }
+    // Rewrites a plan file in the canonical format, or stdin to stdout.
+    Some("format-plan") => {
+      let entry_categories = Config::load()?.entry_categories;
+      match args.next() {
+        Some(path) => {
+          let plan =
+            io::plan::parse_plan(&std::fs::read_to_string(&path)?, &path, &entry_categories)?;
+          std::fs::write(&path, io::plan::format_plan(&plan, &entry_categories))?;
+        }
+        None => {
+          let content = std::io::read_to_string(std::io::stdin())?;
+          let plan = io::plan::parse_plan(&content, "-", &entry_categories)?;
+          print!("{}", io::plan::format_plan(&plan, &entry_categories));
+        }
+      }
+      return Ok(());
+    }
+    _ => {}
+  }
   Gui::run(Settings::default())?;
 
   Ok(())
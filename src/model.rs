@@ -0,0 +1,203 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::structures::{
+  CallType, CrewMember, Group, Person, PersonInfo, PersonToSceneAndScheduleEntry, Role, SceneEntry,
+  SceneSchedulerError, ScheduleEntry, Venue,
+};
+
+/// Version of the JSON format. It has to be increased whenever a change
+/// would make older files be read differently.
+pub const MODEL_VERSION: u32 = 1;
+pub const MODEL_FILE_NAME: &str = "model";
+
+/// Everything read from the excel file, with the names already normalized.
+/// It can be written to JSON and read back instead of the excel file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Model {
+  pub schedule_entries: Vec<ScheduleEntry>,
+  pub scene_entries: Vec<SceneEntry>,
+  #[serde(default)]
+  pub groups: Vec<Group>,
+  #[serde(default)]
+  pub people: Vec<PersonInfo>,
+  #[serde(default)]
+  pub crew: Vec<CrewMember>,
+  #[serde(default)]
+  pub venues: Vec<Venue>,
+  /// The default location of all entries without a room.
+  pub location: String,
+  #[serde(default)]
+  pub mandatory_silent_play: Option<NaiveDate>,
+}
+
+/// The model as written to JSON. The calls show who the scheduler calls when
+/// and are only meant for inspection, they are ignored when reading.
+#[derive(Serialize)]
+struct ModelFile<'a> {
+  version: u32,
+  #[serde(flatten)]
+  model: &'a Model,
+  calls: Vec<PersonCalls<'a>>,
+}
+
+#[derive(Serialize)]
+struct PersonCalls<'a> {
+  person: &'a Person,
+  calls: Vec<Call<'a>>,
+}
+
+#[derive(Serialize)]
+struct Call<'a> {
+  /// UID of the schedule entry.
+  entry: String,
+  role: Option<&'a Role>,
+  call: CallType,
+}
+
+#[derive(Deserialize)]
+struct ModelVersion {
+  version: u32,
+}
+
+impl Model {
+  pub fn to_json(
+    &self,
+    person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+  ) -> Result<String, SceneSchedulerError> {
+    let calls = person_to_scene_and_schedule_entry
+      .iter()
+      .map(|(person, schedule_to_scene_entries)| PersonCalls {
+        person,
+        calls: schedule_to_scene_entries
+          .iter()
+          .map(|(schedule_entry, scene_entry)| Call {
+            entry: format!("{:x}", schedule_entry.uuid),
+            role: scene_entry.map(|scene_entry| &scene_entry.role),
+            call: schedule_entry.call_type_for(*scene_entry, person),
+          })
+          .collect(),
+      })
+      .collect();
+    Ok(serde_json::to_string_pretty(&ModelFile {
+      version: MODEL_VERSION,
+      model: self,
+      calls,
+    })?)
+  }
+
+  pub fn from_json(json: &str, file: &str) -> Result<Self, SceneSchedulerError> {
+    let ModelVersion { version } = serde_json::from_str(json)?;
+    if version != MODEL_VERSION {
+      return Err(SceneSchedulerError::ModelVersion {
+        file: file.to_owned(),
+        found: version,
+        supported: MODEL_VERSION,
+      });
+    }
+    let model: Self = serde_json::from_str(json)?;
+    // Every scene needs its mark, see `SceneEntry::is_scene_silent_play`.
+    if let Some(scene_entry) = model
+      .scene_entries
+      .iter()
+      .find(|scene_entry| scene_entry.silent_play.len() != scene_entry.scenes.len())
+    {
+      return Err(SceneSchedulerError::ModelSilentPlay {
+        file: file.to_owned(),
+        role: scene_entry.role.clone(),
+      });
+    }
+    Ok(model)
+  }
+
+  pub fn read(path: &str) -> Result<Self, SceneSchedulerError> {
+    Self::from_json(&std::fs::read_to_string(path)?, path)
+  }
+}
+
+/// Whether the input file is an exported model instead of an excel file.
+pub fn is_model_file(path: &str) -> bool {
+  match Path::new(path).extension() {
+    Some(extension) => extension.eq_ignore_ascii_case("json"),
+    None => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::structures::Scenes;
+  use chrono::NaiveTime;
+
+  #[test]
+  fn test_model_round_trip() {
+    let schedule_entry = ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      Scenes::Normal(vec!["3".to_string()]),
+      Some("Probebühne".to_string()),
+      None,
+    );
+    let uid = format!("{:x}", schedule_entry.uuid);
+    let model = Model {
+      schedule_entries: vec![schedule_entry],
+      scene_entries: vec![SceneEntry::new(
+        "Gretchen".to_string(),
+        "Anna".to_string(),
+        vec!["3".to_string()],
+        vec![true],
+      )],
+      groups: vec![],
+      people: vec![],
+      crew: vec![],
+      venues: vec![],
+      location: "Theater".to_string(),
+      mandatory_silent_play: None,
+    };
+    let calls = [(&model.schedule_entries[0], Some(&model.scene_entries[0]))];
+    let json = model
+      .to_json(&vec![("Anna".to_string(), vec![&calls[0]])])
+      .unwrap();
+    assert!(json.contains(&format!("\"entry\": \"{}\"", uid)));
+    assert!(json.contains("\"call\": \"SilentPlay\""));
+
+    let read = Model::from_json(&json, "model.json").unwrap();
+    assert_eq!(format!("{:x}", read.schedule_entries[0].uuid), uid);
+    assert_eq!(read.scene_entries[0].who, "Anna");
+    assert_eq!(read.location, "Theater");
+  }
+
+  #[test]
+  fn test_model_silent_play_is_checked() {
+    let json = r#"{"version": 1, "location": "Theater", "schedule_entries": [],
+      "scene_entries": [{"role": "Gretchen", "who": "Anna", "scenes": ["1", "2"], "silent_play": [false]}]}"#;
+    let error = Model::from_json(json, "model.json").unwrap_err();
+    assert!(matches!(
+      error,
+      SceneSchedulerError::ModelSilentPlay { role, .. } if role == "Gretchen"
+    ));
+  }
+
+  #[test]
+  fn test_model_rotation_is_resolved() {
+    // Scene entries only know the dates of a rotation, see `apply_castings`.
+    let json = r#"{"version": 1, "location": "Theater", "schedule_entries": [],
+      "scene_entries": [{"role": "Gretchen", "who": "Anna", "alternates": ["Berta"],
+        "rotation": "Alternating", "scenes": ["1"], "silent_play": [false]}]}"#;
+    assert!(matches!(
+      Model::from_json(json, "model.json"),
+      Err(SceneSchedulerError::SerdeJson(_))
+    ));
+  }
+
+  #[test]
+  fn test_model_version_is_checked() {
+    let error = Model::from_json(r#"{"version": 2}"#, "model.json").unwrap_err();
+    assert!(matches!(
+      error,
+      SceneSchedulerError::ModelVersion { found: 2, .. }
+    ));
+  }
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::Config;
+use crate::structures::SceneSchedulerError;
+use crate::template::{render, EventContext};
+
+/// Characters which are not allowed in file names on at least one of the
+/// common file systems.
+const FORBIDDEN_CHARACTERS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+const TIMESTAMP_DIR_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+/// Lists the files of the last export into the output directory, so that the
+/// next one can remove those it does not write again.
+const WRITTEN_FILES_NAME: &str = ".scene-scheduler-files";
+/// Device names which Windows does not allow as file names, with any
+/// extension.
+const RESERVED_NAMES: [&str; 22] = [
+  "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+  "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Turns a name taken from the spreadsheet into a file name which is safe on
+/// all platforms and cannot leave the output directory.
+pub fn sanitize_file_name(name: &str) -> String {
+  let sanitized = name
+    .chars()
+    .map(|c| {
+      if FORBIDDEN_CHARACTERS.contains(&c) || c.is_control() {
+        '_'
+      } else {
+        c
+      }
+    })
+    .collect::<String>();
+  let sanitized = sanitized.trim().trim_matches('.').trim();
+  if sanitized.is_empty() {
+    return "_".to_owned();
+  }
+  // A reserved name gets a suffix, e.g. `CON.ics` becomes `CON_.ics`.
+  let (stem, extension) = sanitized.split_at(sanitized.find('.').unwrap_or(sanitized.len()));
+  if RESERVED_NAMES
+    .iter()
+    .any(|reserved| stem.trim_end().eq_ignore_ascii_case(reserved))
+  {
+    format!("{}_{}", stem, extension)
+  } else {
+    sanitized.to_owned()
+  }
+}
+
+/// Hands out the paths of all files written during one export. Two names
+/// which would end up in the same file, also on case-insensitive file
+/// systems, are reported as an error instead of overwriting each other.
+/// Files left over from the previous export into the same directory are
+/// removed by [`OutputFiles::finish`].
+#[derive(Debug)]
+pub struct OutputFiles {
+  dir: PathBuf,
+  production: String,
+  file_name_template: String,
+  used: HashMap<PathBuf, String>,
+  /// Relative to `dir`, like `written`.
+  previous: Vec<PathBuf>,
+  written: Vec<PathBuf>,
+  overwritten: Vec<PathBuf>,
+}
+
+impl OutputFiles {
+  /// Creates the output directory. With `timestamped_out_dir` set, a new
+  /// subdirectory named after the current time is used, so earlier exports
+  /// are never overwritten.
+  pub fn create(config: &Config) -> Result<Self, SceneSchedulerError> {
+    let mut dir = PathBuf::from(&config.out_dir);
+    if config.timestamped_out_dir {
+      dir.push(
+        chrono::Local::now()
+          .format(TIMESTAMP_DIR_FORMAT)
+          .to_string(),
+      );
+    }
+    std::fs::create_dir_all(&dir)?;
+    let mut output_files = Self::new(dir, config);
+    match std::fs::read_to_string(output_files.dir.join(WRITTEN_FILES_NAME)) {
+      Ok(previous) => output_files.previous = previous.lines().map(PathBuf::from).collect(),
+      Err(e) if e.kind() == ErrorKind::NotFound => (),
+      Err(e) => return Err(e.into()),
+    }
+    Ok(output_files)
+  }
+
+  fn new(dir: PathBuf, config: &Config) -> Self {
+    Self {
+      dir,
+      production: config.production_name.clone(),
+      file_name_template: config.file_name_template.clone(),
+      used: HashMap::new(),
+      previous: vec![],
+      written: vec![],
+      overwritten: vec![],
+    }
+  }
+
+  /// Path of the calendar of a person, named after the file name template.
+  pub fn person_file(&mut self, person: &str) -> Result<PathBuf, SceneSchedulerError> {
+    let context = EventContext {
+      production: self.production.clone(),
+      person: person.to_owned(),
+      ..Default::default()
+    };
+    let file_name = render(&self.file_name_template, &context);
+    self.register(None, &file_name, person)
+  }
+
+  /// Path of a file named after a room, scene or similar, optionally inside a
+  /// subdirectory of the output directory.
+  pub fn file(
+    &mut self,
+    sub_dir: Option<&str>,
+    name: &str,
+    extension: &str,
+  ) -> Result<PathBuf, SceneSchedulerError> {
+    self.register(sub_dir, &format!("{}.{}", name, extension), name)
+  }
+
+  fn register(
+    &mut self,
+    sub_dir: Option<&str>,
+    file_name: &str,
+    name: &str,
+  ) -> Result<PathBuf, SceneSchedulerError> {
+    let mut dir = self.dir.clone();
+    if let Some(sub_dir) = sub_dir {
+      dir.push(sub_dir);
+      std::fs::create_dir_all(&dir)?;
+    }
+    let file_name = sanitize_file_name(file_name);
+    let key = dir.join(file_name.to_lowercase());
+    if let Some(other) = self.used.get(&key) {
+      return Err(SceneSchedulerError::FileNameCollision {
+        file: file_name,
+        first: other.clone(),
+        second: name.to_owned(),
+      });
+    }
+    self.used.insert(key, name.to_owned());
+    let path = dir.join(file_name);
+    let relative = path.strip_prefix(&self.dir).unwrap_or(&path).to_path_buf();
+    if path.exists() && !self.previous.iter().any(|file| same_file(file, &relative)) {
+      self.overwritten.push(path.clone());
+    }
+    self.written.push(relative);
+    Ok(path)
+  }
+
+  /// Removes the files of the previous export which were not written again
+  /// and lists the written ones for the next export. Returns the files which
+  /// were overwritten although no earlier export wrote them.
+  pub fn finish(self) -> Result<Vec<PathBuf>, SceneSchedulerError> {
+    for file in &self.previous {
+      let inside = file
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+      if inside && !self.written.iter().any(|written| same_file(written, file)) {
+        match std::fs::remove_file(self.dir.join(file)) {
+          Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+          _ => (),
+        }
+      }
+    }
+    let written = self
+      .written
+      .iter()
+      .map(|file| file.to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("\n");
+    std::fs::write(self.dir.join(WRITTEN_FILES_NAME), written)?;
+    Ok(self.overwritten)
+  }
+}
+
+/// Whether both paths name the same file, also on case-insensitive file
+/// systems.
+fn same_file(first: &Path, second: &Path) -> bool {
+  first.to_string_lossy().to_lowercase() == second.to_string_lossy().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sanitize_file_name() {
+    assert_eq!(sanitize_file_name("Anna Müller"), "Anna Müller");
+    assert_eq!(sanitize_file_name("../../etc/passwd"), "_.._etc_passwd");
+    assert_eq!(sanitize_file_name("A/B: C?"), "A_B_ C_");
+    assert_eq!(sanitize_file_name(" .. "), "_");
+  }
+
+  #[test]
+  fn test_sanitize_reserved_file_name() {
+    assert_eq!(sanitize_file_name("CON"), "CON_");
+    assert_eq!(sanitize_file_name("nul.ics"), "nul_.ics");
+    assert_eq!(sanitize_file_name("Com1.tar.gz"), "Com1_.tar.gz");
+    assert_eq!(sanitize_file_name("LPT1 .pdf"), "LPT1 _.pdf");
+    assert_eq!(sanitize_file_name("Conrad.ics"), "Conrad.ics");
+    assert_eq!(sanitize_file_name("COM10"), "COM10");
+  }
+
+  #[test]
+  fn test_person_file_template_and_collisions() {
+    let config = Config {
+      production_name: "Faust".to_string(),
+      file_name_template: "{production}-{person}.ics".to_string(),
+      ..Config::default()
+    };
+    let mut files = OutputFiles::new(PathBuf::from("out"), &config);
+    assert_eq!(
+      files.person_file("Anna/Maria").unwrap(),
+      Path::new("out").join("Faust-Anna_Maria.ics")
+    );
+    assert!(matches!(
+      files.person_file("anna/maria"),
+      Err(SceneSchedulerError::FileNameCollision { .. })
+    ));
+    assert!(files.person_file("Bert").is_ok());
+  }
+
+  #[test]
+  fn test_stale_and_overwritten_files() {
+    let dir = std::env::temp_dir().join(format!("scene-scheduler-out-{}", std::process::id()));
+    let config = Config {
+      out_dir: dir.to_string_lossy().into_owned(),
+      timestamped_out_dir: false,
+      ..Config::default()
+    };
+    let export = |names: &[&str]| {
+      let mut files = OutputFiles::create(&config).unwrap();
+      for name in names {
+        std::fs::write(files.file(Some("rooms"), name, "ics").unwrap(), "").unwrap();
+      }
+      files.finish().unwrap()
+    };
+    assert!(export(&["Saal", "Foyer"]).is_empty());
+    std::fs::write(dir.join("rooms").join("Probebühne.ics"), "").unwrap();
+    let overwritten = export(&["Saal", "Probebühne"]);
+    let exists = |name: &str| dir.join("rooms").join(name).exists();
+    let (saal, foyer) = (exists("Saal.ics"), exists("Foyer.ics"));
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(overwritten, vec![dir.join("rooms").join("Probebühne.ics")]);
+    assert!(saal);
+    assert!(!foyer, "The file of the previous export is removed");
+  }
+}
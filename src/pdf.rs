@@ -0,0 +1,482 @@
+use std::collections::HashMap;
+
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+
+use crate::config::{Config, CALL_SHEET_DIR, PERSON_PDF_DIR};
+use crate::i18n::{Language, Phrase};
+use crate::output::{sanitize_file_name, OutputFiles};
+use crate::sorting::{find_venue, get_schedule_entry_to_persons};
+use crate::structures::{
+  CallType, Person, PersonToSceneAndScheduleEntry, Scene, SceneEntry, SceneSchedulerError, Scenes,
+  ScheduleEntry, Venue,
+};
+use crate::template::format_call;
+
+/// A4 in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const INDENT: f32 = 18.0;
+const REGULAR_FONT: Name = Name(b"F1");
+const BOLD_FONT: Name = Name(b"F2");
+
+/// A line of a printed page.
+#[derive(Debug, PartialEq)]
+enum Line {
+  Title(String),
+  Heading(String),
+  Text(String),
+  Indented(String),
+  Space,
+}
+
+impl Line {
+  fn font_size(&self) -> f32 {
+    match self {
+      Line::Title(_) => 18.0,
+      Line::Heading(_) => 12.0,
+      _ => 11.0,
+    }
+  }
+
+  fn height(&self) -> f32 {
+    match self {
+      Line::Space => 8.0,
+      _ => self.font_size() * 1.4,
+    }
+  }
+}
+
+/// Writes one PDF per person listing all of their calls.
+pub fn write_person_pdf_files(
+  person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+  output_files: &mut OutputFiles,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<(), SceneSchedulerError> {
+  for (person, schedule_to_scene_entries) in person_to_scene_and_schedule_entry {
+    let mut lines = vec![
+      Line::Title(format!("{}: {}", config.production_name, person)),
+      Line::Space,
+    ];
+    for (schedule_entry, scene_entry) in schedule_to_scene_entries {
+      lines.push(Line::Text(format_call(
+        schedule_entry,
+        *scene_entry,
+        default_location,
+      )));
+      let mut details = vec![schedule_entry
+        .call_type_for(*scene_entry, person)
+        .label(language)
+        .to_owned()];
+      if let Some(note) = &schedule_entry.note {
+        details.push(format!("{}: {}", language.tr(Phrase::NoteLabel), note));
+      }
+      lines.push(Line::Indented(details.join(", ")));
+    }
+    write_pdf(
+      &output_files.file(Some(PERSON_PDF_DIR), person, "pdf")?,
+      &lines,
+    )?;
+  }
+  Ok(())
+}
+
+/// Writes one call sheet per schedule entry listing the called people by
+/// scene.
+pub fn write_call_sheet_pdf_files(
+  person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+  venues: &[Venue],
+  output_files: &mut OutputFiles,
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Result<(), SceneSchedulerError> {
+  let schedule_entry_to_persons = get_schedule_entry_to_persons(person_to_scene_and_schedule_entry);
+  let names = call_sheet_names(
+    schedule_entry_to_persons
+      .iter()
+      .map(|(schedule_entry, _)| *schedule_entry),
+    default_location,
+  );
+  for ((schedule_entry, persons), name) in schedule_entry_to_persons.into_iter().zip(names) {
+    let lines = call_sheet_lines(
+      schedule_entry,
+      &persons,
+      venues,
+      config,
+      default_location,
+      language,
+    );
+    write_pdf(
+      &output_files.file(Some(CALL_SHEET_DIR), &name, "pdf")?,
+      &lines,
+    )?;
+  }
+  Ok(())
+}
+
+/// Start and location of every entry. Entries starting at the same time in
+/// the same place, e.g. two all-day entries of one day, are numbered.
+fn call_sheet_names<'a>(
+  schedule_entries: impl Iterator<Item = &'a ScheduleEntry>,
+  default_location: &str,
+) -> Vec<String> {
+  let mut counts = HashMap::<String, usize>::new();
+  schedule_entries
+    .map(|schedule_entry| {
+      let name = format!(
+        "{} {}",
+        schedule_entry
+          .start_stop_date_time()
+          .0
+          .format("%Y-%m-%d_%H-%M"),
+        schedule_entry.location(default_location)
+      );
+      let count = counts
+        .entry(sanitize_file_name(&name).to_lowercase())
+        .or_default();
+      *count += 1;
+      match count {
+        1 => name,
+        count => format!("{} ({})", name, count),
+      }
+    })
+    .collect()
+}
+
+fn call_sheet_lines(
+  schedule_entry: &ScheduleEntry,
+  persons: &[(&Person, Option<&SceneEntry>)],
+  venues: &[Venue],
+  config: &Config,
+  default_location: &str,
+  language: Language,
+) -> Vec<Line> {
+  let mut lines = vec![
+    Line::Title(config.production_name.clone()),
+    Line::Heading(format_call(schedule_entry, None, default_location)),
+  ];
+  for venue in schedule_entry
+    .rooms(default_location)
+    .into_iter()
+    .filter_map(|room| find_venue(room, venues))
+  {
+    lines.push(Line::Text(format!(
+      "{}: {}",
+      language.tr(Phrase::AddressLabel),
+      venue.location()
+    )));
+    if let Some(directions) = &venue.directions {
+      lines.push(Line::Text(format!(
+        "{}: {}",
+        language.tr(Phrase::DirectionsLabel),
+        directions
+      )));
+    }
+  }
+  if let Some(note) = &schedule_entry.note {
+    lines.push(Line::Text(format!(
+      "{}: {}",
+      language.tr(Phrase::NoteLabel),
+      note
+    )));
+  }
+  let describe = |person: &Person, scene_entry: Option<&SceneEntry>| {
+    let mut text = person.clone();
+    if let Some(scene_entry) = scene_entry {
+      text.push_str(&format!(" ({})", scene_entry.role));
+    }
+    if schedule_entry.call_type_for(scene_entry, person) == CallType::Observe {
+      text.push_str(&format!(" – {}", CallType::Observe.label(language)));
+    }
+    text
+  };
+  let scenes = match &schedule_entry.scenes {
+    Scenes::Normal(scenes) => scenes.as_slice(),
+    Scenes::Special(_) => &[],
+  };
+  for scene in scenes {
+    lines.push(Line::Space);
+    let mut heading = format!("{} {}", language.tr(Phrase::SceneLabel), scene);
+    // With parallel sessions everyone needs to know where the scene is.
+    if let Some(session) = schedule_entry
+      .sessions
+      .iter()
+      .find(|session| session.scenes.contains(scene))
+    {
+      heading.push_str(&format!(
+        " – {}",
+        session.room.as_deref().unwrap_or(default_location)
+      ));
+    }
+    lines.push(Line::Heading(heading));
+    for (person, scene_entry) in persons {
+      if plays_in(*scene_entry, std::slice::from_ref(scene)) {
+        lines.push(Line::Indented(describe(person, *scene_entry)));
+      }
+    }
+  }
+  // Everyone not listed under a scene, e.g. the crew or everybody for special
+  // entries.
+  let others = persons
+    .iter()
+    .filter(|(_, scene_entry)| !plays_in(*scene_entry, scenes))
+    .collect::<Vec<_>>();
+  if !others.is_empty() {
+    lines.push(Line::Space);
+    lines.push(Line::Heading(
+      language
+        .tr(if scenes.is_empty() {
+          Phrase::CastLabel
+        } else {
+          Phrase::OthersLabel
+        })
+        .to_owned(),
+    ));
+    for (person, scene_entry) in others {
+      lines.push(Line::Indented(describe(person, *scene_entry)));
+    }
+  }
+  lines
+}
+
+fn plays_in(scene_entry: Option<&SceneEntry>, scenes: &[Scene]) -> bool {
+  match scene_entry {
+    Some(scene_entry) => scenes
+      .iter()
+      .any(|scene| scene_entry.scenes.contains(scene)),
+    None => false,
+  }
+}
+
+fn write_pdf(path: &std::path::Path, lines: &[Line]) -> Result<(), SceneSchedulerError> {
+  let catalog_id = Ref::new(1);
+  let page_tree_id = Ref::new(2);
+  let regular_font_id = Ref::new(3);
+  let bold_font_id = Ref::new(4);
+  let mut next_id = 5;
+  let mut pdf = Pdf::new();
+  pdf.catalog(catalog_id).pages(page_tree_id);
+  pdf
+    .type1_font(regular_font_id)
+    .base_font(Name(b"Helvetica"))
+    .encoding_predefined(Name(b"WinAnsiEncoding"));
+  pdf
+    .type1_font(bold_font_id)
+    .base_font(Name(b"Helvetica-Bold"))
+    .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+  let mut page_ids = vec![];
+  for page in paginate(lines) {
+    let page_id = Ref::new(next_id);
+    let content_id = Ref::new(next_id + 1);
+    next_id += 2;
+    page_ids.push(page_id);
+
+    let mut content = Content::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for line in &page {
+      y -= line.height();
+      let (font, x, text) = match line {
+        Line::Title(text) | Line::Heading(text) => (BOLD_FONT, MARGIN, text),
+        Line::Text(text) => (REGULAR_FONT, MARGIN, text),
+        Line::Indented(text) => (REGULAR_FONT, MARGIN + INDENT, text),
+        Line::Space => continue,
+      };
+      content
+        .begin_text()
+        .set_font(font, line.font_size())
+        .next_line(x, y)
+        .show(Str(&win_ansi(text)))
+        .end_text();
+    }
+    pdf.stream(content_id, &content.finish());
+
+    let mut page = pdf.page(page_id);
+    page
+      .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+      .parent(page_tree_id)
+      .contents(content_id);
+    page
+      .resources()
+      .fonts()
+      .pair(REGULAR_FONT, regular_font_id)
+      .pair(BOLD_FONT, bold_font_id);
+  }
+  let page_count = page_ids.len() as i32;
+  pdf.pages(page_tree_id).kids(page_ids).count(page_count);
+  std::fs::write(path, pdf.finish())?;
+  Ok(())
+}
+
+/// Wraps long lines and distributes them onto pages.
+fn paginate(lines: &[Line]) -> Vec<Vec<Line>> {
+  let mut pages = vec![vec![]];
+  let mut height = 0.0;
+  for line in lines.iter().flat_map(wrap) {
+    if height + line.height() > PAGE_HEIGHT - 2.0 * MARGIN {
+      pages.push(vec![]);
+      height = 0.0;
+      if line == Line::Space {
+        continue;
+      }
+    }
+    height += line.height();
+    pages.last_mut().unwrap().push(line);
+  }
+  pages
+}
+
+fn wrap(line: &Line) -> Vec<Line> {
+  let (text, indent, make): (&str, f32, fn(String) -> Line) = match line {
+    Line::Title(text) => (text, 0.0, Line::Title),
+    Line::Heading(text) => (text, 0.0, Line::Heading),
+    Line::Text(text) => (text, 0.0, Line::Text),
+    Line::Indented(text) => (text, INDENT, Line::Indented),
+    Line::Space => return vec![Line::Space],
+  };
+  // Helvetica is about half as wide as high on average.
+  let max_chars = ((PAGE_WIDTH - 2.0 * MARGIN - indent) / (line.font_size() * 0.5)) as usize;
+  let mut wrapped = vec![];
+  let mut current = String::new();
+  for word in text.split(' ') {
+    if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+      wrapped.push(make(std::mem::take(&mut current)));
+    }
+    if !current.is_empty() {
+      current.push(' ');
+    }
+    current.push_str(word);
+  }
+  wrapped.push(make(current));
+  wrapped
+}
+
+/// Encodes text for the standard fonts. Characters outside of the Windows
+/// code page 1252 are replaced by '?'.
+fn win_ansi(text: &str) -> Vec<u8> {
+  text
+    .chars()
+    .map(|c| match c {
+      '\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => c as u8,
+      '€' => 0x80,
+      '…' => 0x85,
+      '‘' => 0x91,
+      '’' => 0x92,
+      '“' => 0x93,
+      '”' => 0x94,
+      '•' => 0x95,
+      '–' => 0x96,
+      '—' => 0x97,
+      _ => b'?',
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{NaiveDate, NaiveTime};
+
+  #[test]
+  fn test_call_sheet_lists_people_by_scene() {
+    let schedule_entry = ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      Scenes::Normal(vec!["1".to_string(), "2".to_string()]),
+      Some("Probebühne".to_string()),
+      None,
+    );
+    let gretchen = SceneEntry::new(
+      "Gretchen".to_string(),
+      "Anna".to_string(),
+      vec!["2".to_string()],
+      vec![false],
+    );
+    let (anna, bert) = ("Anna".to_string(), "Bert".to_string());
+    let persons = vec![(&anna, Some(&gretchen)), (&bert, None)];
+    let lines = call_sheet_lines(
+      &schedule_entry,
+      &persons,
+      &[],
+      &Config::default(),
+      "Theater",
+      Language::English,
+    );
+    assert_eq!(
+      lines[1..],
+      [
+        Line::Heading("01.05.2022 10:00, Probebühne: 1, 2".to_string()),
+        Line::Space,
+        Line::Heading("Scene 1".to_string()),
+        Line::Space,
+        Line::Heading("Scene 2".to_string()),
+        Line::Indented("Anna (Gretchen)".to_string()),
+        Line::Space,
+        Line::Heading("Others".to_string()),
+        Line::Indented("Bert".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_call_sheet_shows_venue() {
+    let schedule_entry = ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      Scenes::Special("Hauptprobe".to_string()),
+      None,
+      None,
+    );
+    let venues = [Venue {
+      room: "Theater".to_string(),
+      address: Some("Hauptstrasse 1, Zürich".to_string()),
+      building: None,
+      floor: Some("EG".to_string()),
+      directions: Some("Bühneneingang hinten".to_string()),
+      coordinates: None,
+    }];
+    let lines = call_sheet_lines(
+      &schedule_entry,
+      &[],
+      &venues,
+      &Config::default(),
+      "theater",
+      Language::English,
+    );
+    assert_eq!(
+      lines[2..4],
+      [
+        Line::Text("Address: Theater, EG, Hauptstrasse 1, Zürich".to_string()),
+        Line::Text("Directions: Bühneneingang hinten".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_call_sheet_names_are_unique() {
+    let date = NaiveDate::from_ymd_opt(2024, 4, 12).unwrap();
+    let all_day = |scene: &str| {
+      ScheduleEntry::new(
+        date,
+        (NaiveTime::MIN, None),
+        Scenes::Special(scene.to_string()),
+        None,
+        None,
+      )
+      .all_day(date)
+    };
+    let (setup, meeting) = (all_day("Aufbau"), all_day("Besprechung"));
+    assert_eq!(
+      call_sheet_names([&setup, &meeting].into_iter(), "Theater"),
+      vec!["2024-04-12_00-00 Theater", "2024-04-12_00-00 Theater (2)"]
+    );
+  }
+
+  #[test]
+  fn test_win_ansi() {
+    assert_eq!(win_ansi("Müller – 5€ ✓"), b"M\xfcller \x96 5\x80 ?");
+  }
+}
@@ -1,23 +1,310 @@
+use crate::attendance::write_attendance_matrix;
+use crate::caldav::push_to_caldav;
 use crate::config::*;
+use crate::html::write_html_site;
+use crate::i18n::{Language, Phrase};
 use crate::ics::*;
-use crate::io::{excel::*, parsing::excel::*};
+use crate::io::{calendar::*, excel::*, parsing::calendar::*, parsing::excel::*, plan::*};
+use crate::mail::write_emails;
+use crate::model::{is_model_file, Model, MODEL_FILE_NAME};
+use crate::output::OutputFiles;
+use crate::pdf::{write_call_sheet_pdf_files, write_person_pdf_files};
 use crate::sorting::*;
-use crate::structures::SceneSchedulerError;
+use crate::structures::{
+  ImportMode, Person, PersonToSceneAndScheduleEntry, Room, SceneSchedulerError, ScheduleEntry,
+  ScheduleEntryToCast,
+};
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct Scheduler {
   pub config: Config,
 }
+
+/// What the user is told after an export.
+#[derive(Debug, Default)]
+pub struct Export {
+  /// People who were not sent an email because they have no address.
+  pub without_email: Vec<Person>,
+  /// Files which were overwritten although no earlier export wrote them.
+  pub overwritten: Vec<PathBuf>,
+}
+
+/// Calendars kept in memory, e.g. to be served as feeds.
+#[derive(Debug, Default)]
+pub struct Calendars {
+  pub persons: Vec<(Person, String)>,
+  pub rooms: Vec<(Room, String)>,
+  pub master: String,
+}
+
 impl Scheduler {
-  pub fn process(&self) -> Result<(), SceneSchedulerError> {
+  /// Writes all enabled exports.
+  pub fn process(&self, language: Language) -> Result<Export, SceneSchedulerError> {
+    let model = self.read_model()?;
+    self.with_calls(
+      &model,
+      |person_to_schedule_and_scene_entries, schedule_entry_to_cast| {
+        let participants = Participants::new(
+          person_to_schedule_and_scene_entries,
+          &model.people,
+          &model.venues,
+          &self.config,
+        );
+        let location = &model.location;
+        let mut output_files = OutputFiles::create(&self.config)?;
+        let mut without_email = vec![];
+        if self.config.export_json {
+          std::fs::write(
+            output_files.file(None, MODEL_FILE_NAME, "json")?,
+            model.to_json(person_to_schedule_and_scene_entries)?,
+          )?;
+        }
+        write_ics_file(
+          person_to_schedule_and_scene_entries,
+          &participants,
+          &mut output_files,
+          &self.config,
+          location,
+          language,
+        )?;
+        if self.config.export_master_calendar {
+          write_master_ics_file(
+            schedule_entry_to_cast,
+            &participants,
+            &mut output_files,
+            &self.config,
+            location,
+            language,
+          )?;
+        }
+        if self.config.export_room_calendars {
+          write_room_ics_files(
+            schedule_entry_to_cast,
+            &participants,
+            &mut output_files,
+            &self.config,
+            location,
+            language,
+          )?;
+        }
+        if self.config.export_scene_calendars {
+          write_scene_ics_files(
+            schedule_entry_to_cast,
+            &get_all_scenes(&model.scene_entries),
+            &participants,
+            &mut output_files,
+            &self.config,
+            location,
+            language,
+          )?;
+        }
+        if self.config.export_pdf {
+          write_person_pdf_files(
+            person_to_schedule_and_scene_entries,
+            &mut output_files,
+            &self.config,
+            location,
+            language,
+          )?;
+          write_call_sheet_pdf_files(
+            person_to_schedule_and_scene_entries,
+            &model.venues,
+            &mut output_files,
+            &self.config,
+            location,
+            language,
+          )?;
+        }
+        if self.config.export_html {
+          write_html_site(
+            person_to_schedule_and_scene_entries,
+            schedule_entry_to_cast,
+            &get_all_scenes(&model.scene_entries),
+            &participants,
+            &mut output_files,
+            &self.config,
+            location,
+            language,
+          )?;
+        }
+        if self.config.export_attendance {
+          write_attendance_matrix(person_to_schedule_and_scene_entries, &self.config, language)?;
+        }
+        if self.config.export_emails {
+          without_email = write_emails(
+            person_to_schedule_and_scene_entries,
+            &model.people,
+            &participants,
+            &mut output_files,
+            &self.config,
+            location,
+            language,
+            chrono::Local::now().date_naive(),
+          )?;
+        }
+        if self.config.export_caldav {
+          let caldav = self
+            .config
+            .caldav
+            .as_ref()
+            .ok_or_else(|| SceneSchedulerError::CalDav {
+              message: Phrase::CalDavNotConfigured,
+              url: String::new(),
+              error: String::new(),
+            })?;
+          push_to_caldav(
+            person_to_schedule_and_scene_entries,
+            &participants,
+            caldav,
+            &self.config,
+            location,
+            language,
+          )?;
+        }
+        Ok(Export {
+          without_email,
+          overwritten: output_files.finish()?,
+        })
+      },
+    )
+  }
+
+  /// Creates the person, room and master calendars without writing them.
+  pub fn calendars(&self, language: Language) -> Result<Calendars, SceneSchedulerError> {
+    let model = self.read_model()?;
+    self.with_calls(
+      &model,
+      |person_to_schedule_and_scene_entries, schedule_entry_to_cast| {
+        let participants = Participants::new(
+          person_to_schedule_and_scene_entries,
+          &model.people,
+          &model.venues,
+          &self.config,
+        );
+        let location = &model.location;
+        let persons = person_to_schedule_and_scene_entries
+          .iter()
+          .map(|(person, entries)| {
+            let calendar = create_person_calendar(
+              person,
+              entries,
+              &participants,
+              &self.config,
+              location,
+              language,
+            )?;
+            Ok((person.clone(), calendar.to_string()))
+          })
+          .collect::<Result<_, SceneSchedulerError>>()?;
+        let rooms = create_room_calendars(
+          schedule_entry_to_cast,
+          &participants,
+          &self.config,
+          location,
+          language,
+        )?
+        .into_iter()
+        .map(|(room, calendar)| (room, calendar.to_string()))
+        .collect();
+        let master = create_master_calendar(
+          schedule_entry_to_cast,
+          &participants,
+          &self.config,
+          location,
+          language,
+        )?
+        .to_string();
+        Ok(Calendars {
+          persons,
+          rooms,
+          master,
+        })
+      },
+    )
+  }
+
+  /// The model as JSON together with the calls worked out from it.
+  pub fn model_json(&self) -> Result<String, SceneSchedulerError> {
+    let model = self.read_model()?;
+    self.with_calls(&model, |person_to_schedule_and_scene_entries, _| {
+      model.to_json(person_to_schedule_and_scene_entries)
+    })
+  }
+
+  /// The model in the plain text plan format.
+  pub fn model_plan(&self) -> Result<String, SceneSchedulerError> {
+    Ok(format_plan(
+      &self.read_model()?,
+      &self.config.entry_categories,
+    ))
+  }
+
+  /// Reads the excel file or the plan, or a model exported before if the
+  /// file is JSON. The config is applied to all of them in the same way.
+  fn read_model(&self) -> Result<Model, SceneSchedulerError> {
+    let path = &self.config.excel_file_path;
+    let mut model = if is_model_file(path) {
+      Model::read(path)?
+    } else if is_plan_file(path) {
+      parse_plan(
+        &std::fs::read_to_string(path)?,
+        path,
+        &self.config.entry_categories,
+      )?
+    } else {
+      self.read_excel_model()?
+    };
+    self.import_calendars(&mut model.schedule_entries)?;
+    model
+      .schedule_entries
+      .sort_by_key(|schedule_entry| (schedule_entry.date, schedule_entry.start_stop_time.0));
+    apply_entry_categories(&mut model.schedule_entries, &self.config.entry_categories);
+    model.schedule_entries = infer_stop_times(model.schedule_entries, &self.config);
+    apply_castings(
+      &mut model.scene_entries,
+      &self.config.castings,
+      &model.schedule_entries,
+    );
+    // An exported model already contains the directories of the config.
+    let mut people = self.config.people.clone();
+    people.extend(
+      model
+        .people
+        .into_iter()
+        .filter(|info| find_person_info(&info.name, &self.config.people).is_none()),
+    );
+    model.people = people;
+    let mut venues = self.config.venues.clone();
+    venues.extend(
+      model
+        .venues
+        .into_iter()
+        .filter(|venue| find_venue(&venue.room, &self.config.venues).is_none()),
+    );
+    model.venues = venues;
+    model.crew = self.config.crew.clone();
+    normalize_names(
+      &mut model.scene_entries,
+      &mut model.groups,
+      &mut model.crew,
+      &model.people,
+    );
+    Ok(model)
+  }
+
+  /// Reads the sheets of the excel file as they are. The config is applied
+  /// by `read_model`.
+  fn read_excel_model(&self) -> Result<Model, SceneSchedulerError> {
     let (schedule_excel_range, schedule_excel_worksheet_name) =
       read_excel(&self.config.excel_file_path, self.config.schedule_sheet_num)?;
     let schedule_entries = parse_schedule_plan_content(
       &schedule_excel_range,
       &self.config.excel_file_path,
       &schedule_excel_worksheet_name,
+      &self.config.entry_categories,
     )?;
-    let (mandatory_silet_play, location): (_, String) = parse_mandatory_silent_play_and_place(
+    let (mandatory_silent_play, location): (_, String) = parse_mandatory_silent_play_and_place(
       &schedule_excel_range,
       &self.config.excel_file_path,
       &schedule_excel_worksheet_name,
@@ -29,22 +316,182 @@ impl Scheduler {
       &self.config.excel_file_path,
       &scene_excel_worksheet_name,
     )?;
-    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let groups = match self.config.groups_sheet_num {
+      Some(groups_sheet_num) => {
+        let (groups_excel_range, groups_excel_worksheet_name) =
+          read_excel(&self.config.excel_file_path, groups_sheet_num)?;
+        parse_groups_content(
+          &groups_excel_range,
+          &self.config.excel_file_path,
+          &groups_excel_worksheet_name,
+        )?
+      }
+      None => vec![],
+    };
+    let people = match self.config.people_sheet_num {
+      Some(people_sheet_num) => {
+        let (people_excel_range, people_excel_worksheet_name) =
+          read_excel(&self.config.excel_file_path, people_sheet_num)?;
+        parse_people_content(
+          &people_excel_range,
+          &self.config.excel_file_path,
+          &people_excel_worksheet_name,
+        )?
+      }
+      None => vec![],
+    };
+    let venues = match self.config.venues_sheet_num {
+      Some(venues_sheet_num) => {
+        let (venues_excel_range, venues_excel_worksheet_name) =
+          read_excel(&self.config.excel_file_path, venues_sheet_num)?;
+        parse_venues_content(
+          &venues_excel_range,
+          &self.config.excel_file_path,
+          &venues_excel_worksheet_name,
+        )?
+      }
+      None => vec![],
+    };
+    Ok(Model {
+      schedule_entries,
+      scene_entries,
+      groups,
+      people,
+      crew: vec![],
+      venues,
+      location,
+      mandatory_silent_play,
+    })
+  }
+
+  /// Adds the events of the imported calendars to the schedule entries, or
+  /// replaces them. Events exported before are recognized by their UID, also
+  /// if their stop time was inferred.
+  fn import_calendars(
+    &self,
+    schedule_entries: &mut Vec<ScheduleEntry>,
+  ) -> Result<(), SceneSchedulerError> {
+    let imports = &self.config.calendar_imports;
+    if imports.is_empty() {
+      return Ok(());
+    }
+    if imports
+      .iter()
+      .any(|import| import.mode == ImportMode::Replace)
+    {
+      schedule_entries.clear();
+    }
+    for import in imports {
+      let events = read_calendar(&import.path)?;
+      for schedule_entry in parse_calendar_events(&events, import, &self.config.entry_categories)? {
+        if !schedule_entries.iter().any(|existing| {
+          existing.uuid == schedule_entry.uuid || existing.is_with_stop_time(&schedule_entry)
+        }) {
+          schedule_entries.push(schedule_entry);
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Works out who is called when and passes the result to `f`.
+  fn with_calls<T>(
+    &self,
+    model: &Model,
+    f: impl FnOnce(
+      &PersonToSceneAndScheduleEntry,
+      &ScheduleEntryToCast,
+    ) -> Result<T, SceneSchedulerError>,
+  ) -> Result<T, SceneSchedulerError> {
+    let schedule_to_scene_entries =
+      get_schedule_to_scene_entry(&model.schedule_entries, &model.scene_entries);
     let filtered_schedule_to_scene_entries =
-      if let Some(mandatory_silet_play) = &mandatory_silet_play {
-        filter_by_silent_play(&schedule_to_scene_entries, mandatory_silet_play)
+      if let Some(mandatory_silent_play) = &model.mandatory_silent_play {
+        filter_by_silent_play(&schedule_to_scene_entries, mandatory_silent_play)
       } else {
         schedule_to_scene_entries
       };
-    let person_to_schedule_and_scene_entries =
-      get_person_to_scene_and_schedule_entry(&filtered_schedule_to_scene_entries);
-
-    write_ics_file(
+    let mut person_to_schedule_and_scene_entries =
+      get_person_to_scene_and_schedule_entry(&filtered_schedule_to_scene_entries, &model.groups);
+    let schedule_entries_without_scene = model
+      .schedule_entries
+      .iter()
+      .map(|schedule_entry| (schedule_entry, None))
+      .collect::<Vec<_>>();
+    add_crew_to_person_entries(
+      &mut person_to_schedule_and_scene_entries,
+      &model.crew,
+      &schedule_entries_without_scene,
+      &model.groups,
+      &model.location,
+    );
+    let schedule_entry_to_cast =
+      get_schedule_entry_to_cast(&filtered_schedule_to_scene_entries, &model.scene_entries);
+    f(
       &person_to_schedule_and_scene_entries,
-      &self.config.out_dir,
-      &location,
-    )?;
+      &schedule_entry_to_cast,
+    )
+  }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::structures::CalendarImport;
+  use chrono::NaiveTime;
+
+  #[test]
+  fn test_stop_times_are_inferred_after_the_import() {
+    let dir = std::env::temp_dir().join(format!("scene-scheduler-import-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let plan = dir.join("faust.plan");
+    std::fs::write(
+      &plan,
+      "location: Theater\n[schedule]\n2024-03-05 18:00 | Sz 1\n2024-03-05 21:00 | Sz 2\n",
+    )
+    .unwrap();
+    // The first entry as exported before with its stop time inferred from
+    // the second one, and an event between the two.
+    let calendar = dir.join("venue.ics");
+    std::fs::write(
+      &calendar,
+      "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+       BEGIN:VEVENT\r\nUID:a\r\nSUMMARY:1\r\nDTSTART:20240305T180000\r\nDTEND:20240305T210000\r\nEND:VEVENT\r\n\
+       BEGIN:VEVENT\r\nUID:b\r\nSUMMARY:3\r\nDTSTART:20240305T193000\r\nDTEND:20240305T203000\r\nEND:VEVENT\r\n\
+       END:VCALENDAR\r\n",
+    )
+    .unwrap();
+    let scheduler = Scheduler {
+      config: Config {
+        excel_file_path: plan.to_string_lossy().into_owned(),
+        calendar_imports: vec![CalendarImport {
+          path: calendar.to_string_lossy().into_owned(),
+          mode: ImportMode::Merge,
+          summary_pattern: None,
+          description_pattern: None,
+        }],
+        end_at_next_entry: true,
+        ..Config::default()
+      },
+    };
+    let model = scheduler.read_model();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+    assert_eq!(
+      model
+        .unwrap()
+        .schedule_entries
+        .iter()
+        .map(|schedule_entry| (
+          schedule_entry.start_stop_time,
+          schedule_entry.stop_time_inferred
+        ))
+        .collect::<Vec<_>>(),
+      vec![
+        ((time(18, 0), Some(time(19, 30))), true),
+        ((time(19, 30), Some(time(20, 30))), false),
+        ((time(21, 0), None), false),
+      ]
+    );
   }
 }
@@ -0,0 +1,245 @@
+use std::time::SystemTime;
+
+use hmac::{Hmac, Mac};
+use percent_encoding::percent_decode_str;
+use rand::RngCore;
+use sha2::Sha256;
+use tiny_http::{Header, Response, Server};
+
+use crate::config::{Config, MASTER_CALENDAR_NAME, ROOM_CALENDAR_DIR};
+use crate::i18n::Language;
+use crate::output::sanitize_file_name;
+use crate::scheduler::{Calendars, Scheduler};
+use crate::structures::SceneSchedulerError;
+
+pub const PERSON_FEED_DIR: &str = "people";
+const TOKEN_LENGTH: usize = 32;
+
+/// Serves the person, room and master calendars as feeds which calendar
+/// apps can subscribe to. The excel file is read again whenever it changes.
+/// Every feed contains the e-mail addresses of the people called, so all of
+/// them are only served with their token.
+pub fn serve(mut config: Config, language: Language) -> Result<(), SceneSchedulerError> {
+  if config.feed_secret.is_none() {
+    // The secret has to stay the same, otherwise all shared links break.
+    config.feed_secret = Some(generate_secret());
+    config.save()?;
+  }
+  let server =
+    Server::http(&config.serve_address).map_err(|e| SceneSchedulerError::Serve(e.to_string()))?;
+  let mut feeds = Feeds {
+    scheduler: Scheduler { config },
+    language,
+    calendars: None,
+    modified: None,
+  };
+  feeds.reload_if_changed();
+  feeds.print_urls();
+
+  for request in server.incoming_requests() {
+    feeds.reload_if_changed();
+    let response = match feeds.get(request.url()) {
+      Some(calendar) => Response::from_string(calendar).with_header(
+        Header::from_bytes("Content-Type", "text/calendar; charset=utf-8")
+          .expect("static header is valid"),
+      ),
+      None => Response::from_string("Not found").with_status_code(404),
+    };
+    if let Err(e) = request.respond(response) {
+      eprintln!("{}", SceneSchedulerError::from(e).localized(language));
+    }
+  }
+  Ok(())
+}
+
+struct Feeds {
+  scheduler: Scheduler,
+  language: Language,
+  calendars: Option<Calendars>,
+  modified: Option<SystemTime>,
+}
+
+impl Feeds {
+  /// Reads the excel file again if it was modified. On errors the previous
+  /// calendars are kept, so a half saved file does not empty the feeds.
+  fn reload_if_changed(&mut self) {
+    let modified = std::fs::metadata(&self.scheduler.config.excel_file_path)
+      .and_then(|metadata| metadata.modified())
+      .ok();
+    if self.calendars.is_some() && modified == self.modified {
+      return;
+    }
+    match self.scheduler.calendars(self.language) {
+      Ok(calendars) => {
+        self.calendars = Some(calendars);
+        self.modified = modified;
+      }
+      Err(e) => eprintln!("{}", e.localized(self.language)),
+    }
+  }
+
+  fn print_urls(&self) {
+    let calendars = match &self.calendars {
+      Some(calendars) => calendars,
+      None => return,
+    };
+    let base = format!("webcal://{}", self.scheduler.config.serve_address);
+    println!(
+      "{}/{}/{}.ics",
+      base,
+      MASTER_CALENDAR_NAME,
+      self.token(MASTER_CALENDAR_NAME)
+    );
+    for (room, _) in &calendars.rooms {
+      println!(
+        "{}: {}/{}/{}.ics",
+        room,
+        base,
+        ROOM_CALENDAR_DIR,
+        self.token(&room_feed(room))
+      );
+    }
+    for (person, _) in &calendars.persons {
+      println!(
+        "{}: {}/{}/{}.ics",
+        person,
+        base,
+        PERSON_FEED_DIR,
+        self.token(person)
+      );
+    }
+  }
+
+  fn secret(&self) -> &str {
+    self.scheduler.config.feed_secret.as_deref().unwrap_or("")
+  }
+
+  fn token(&self, feed: &str) -> String {
+    feed_token(self.secret(), feed)
+  }
+
+  fn get(&self, url: &str) -> Option<String> {
+    let calendars = self.calendars.as_ref()?;
+    let path = url.split('?').next().unwrap_or(url);
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+    let (dir, name) = match path.trim_start_matches('/').rsplit_once('/') {
+      Some((dir, name)) => (Some(dir), name),
+      None => (None, path.trim_start_matches('/')),
+    };
+    let name = name.strip_suffix(".ics")?;
+    let secret = self.secret();
+    match dir {
+      Some(MASTER_CALENDAR_NAME) if is_feed_token(secret, MASTER_CALENDAR_NAME, name) => {
+        Some(calendars.master.clone())
+      }
+      Some(ROOM_CALENDAR_DIR) => calendars
+        .rooms
+        .iter()
+        .find(|(room, _)| is_feed_token(secret, &room_feed(room), name))
+        .map(|(_, calendar)| calendar.clone()),
+      Some(PERSON_FEED_DIR) => calendars
+        .persons
+        .iter()
+        .find(|(person, _)| is_feed_token(secret, person, name))
+        .map(|(_, calendar)| calendar.clone()),
+      _ => None,
+    }
+  }
+}
+
+fn generate_secret() -> String {
+  let mut secret = [0u8; 32];
+  rand::thread_rng().fill_bytes(&mut secret);
+  hex(&secret)
+}
+
+/// The feed of a room is signed together with the directory, so that its
+/// token differs from the one of a person with the same name.
+fn room_feed(room: &str) -> String {
+  format!("{}/{}", ROOM_CALENDAR_DIR, sanitize_file_name(room))
+}
+
+fn feed_mac(secret: &str, feed: &str) -> Hmac<Sha256> {
+  let mut mac =
+    Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+  mac.update(feed.as_bytes());
+  mac
+}
+
+/// Token in the URL of a feed, for a person just their name. It can only be
+/// computed with the secret, so the feeds cannot be guessed.
+fn feed_token(secret: &str, feed: &str) -> String {
+  let mut token = hex(&feed_mac(secret, feed).finalize().into_bytes());
+  token.truncate(TOKEN_LENGTH);
+  token
+}
+
+/// Checks the token of a request in constant time.
+fn is_feed_token(secret: &str, feed: &str, token: &str) -> bool {
+  if token.len() != TOKEN_LENGTH {
+    return false;
+  }
+  let bytes = (0..TOKEN_LENGTH)
+    .step_by(2)
+    .map(|i| {
+      token
+        .get(i..i + 2)
+        .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+    })
+    .collect::<Option<Vec<_>>>();
+  match bytes {
+    Some(bytes) => feed_mac(secret, feed).verify_truncated_left(&bytes).is_ok(),
+    None => false,
+  }
+}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_feed_urls() {
+    let config = Config {
+      feed_secret: Some("secret".to_string()),
+      ..Config::default()
+    };
+    let feeds = Feeds {
+      scheduler: Scheduler { config },
+      language: Language::German,
+      calendars: Some(Calendars {
+        persons: vec![("Anna".to_string(), "anna".to_string())],
+        rooms: vec![("Probebühne 1".to_string(), "room".to_string())],
+        master: "master".to_string(),
+      }),
+      modified: None,
+    };
+    let token = feeds.token("Anna");
+    assert_eq!(token.len(), TOKEN_LENGTH);
+    assert_ne!(token, feed_token("other secret", "Anna"));
+    assert_eq!(
+      feeds.get(&format!("/people/{}.ics", token)).as_deref(),
+      Some("anna")
+    );
+    assert_eq!(feeds.get("/people/Anna.ics"), None);
+    let room_token = feeds.token(&room_feed("Probebühne 1"));
+    assert_ne!(room_token, feeds.token("Probebühne 1"));
+    assert_eq!(
+      feeds.get(&format!("/rooms/{}.ics", room_token)).as_deref(),
+      Some("room")
+    );
+    assert_eq!(feeds.get("/rooms/Probeb%C3%BChne%201.ics"), None);
+    assert_eq!(
+      feeds
+        .get(&format!("/master/{}.ics?x=1", feeds.token("master")))
+        .as_deref(),
+      Some("master")
+    );
+    assert_eq!(feeds.get("/master.ics"), None);
+    assert_eq!(feeds.get(&format!("/master/{}.ics", token)), None);
+    assert_eq!(feeds.get("/config.json"), None);
+  }
+}
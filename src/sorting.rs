@@ -1,13 +1,40 @@
-use crate::structures::{Person, PersonToSceneAndScheduleEntry, SceneEntry, Scenes, ScheduleEntry};
+use crate::config::Config;
+use crate::structures::{
+  expand_group, CallType, Called, CastDate, CastRotation, Casting, CrewMember, CrewRule,
+  EntryCategory, EntryType, Group, Person, PersonInfo, PersonToSceneAndScheduleEntry, Rotation,
+  Scene, SceneEntry, Scenes, ScheduleEntry, ScheduleEntryToCast, ScheduleEntryToPersons, Venue,
+};
 use chrono::NaiveDate;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+/// Takes who is called to the schedule entries from the categories of their
+/// types, types without one call by scenes.
+pub fn apply_entry_categories(
+  schedule_entries: &mut [ScheduleEntry],
+  entry_categories: &[EntryCategory],
+) {
+  for schedule_entry in schedule_entries {
+    schedule_entry.called = entry_categories
+      .iter()
+      .find(|category| category.entry_type == schedule_entry.entry_type)
+      .map(|category| category.called)
+      .unwrap_or_default();
+  }
+}
+
+/// Pairs every schedule entry with the scene entries it calls. Which ones
+/// depends on who is called to it. Entries only for the crew are kept
+/// without a scene entry, but nobody of the cast is called to them.
 pub fn get_schedule_to_scene_entry<'a>(
   schedule_entries: &'a Vec<ScheduleEntry>,
   scene_entries: &'a Vec<SceneEntry>,
 ) -> Vec<(&'a ScheduleEntry, Option<&'a SceneEntry>)> {
   let mut schedule_to_scene_entries = vec![];
   for schedule_entry in schedule_entries {
+    if schedule_entry.called != Called::Scenes {
+      schedule_to_scene_entries.push((schedule_entry, None));
+      continue;
+    }
     match &schedule_entry.scenes {
       Scenes::Special(_) => {
         // When it is a special scene, all scenes are played or not yet known
@@ -36,21 +63,29 @@ pub fn get_schedule_to_scene_entry<'a>(
 
 pub fn get_person_to_scene_and_schedule_entry<'a>(
   schedule_to_scene_entries: &'a [(&'a ScheduleEntry, Option<&'a SceneEntry>)],
+  groups: &[Group],
 ) -> PersonToSceneAndScheduleEntry<'a> {
   let all_persons = schedule_to_scene_entries
     .iter()
-    .filter_map(|(_, scene_entry)| scene_entry.map(|x| x.who.to_owned()))
+    .filter_map(|(_, scene_entry)| *scene_entry)
+    .flat_map(|scene_entry| {
+      scene_entry
+        .cast()
+        .into_iter()
+        .chain(scene_entry.observing())
+    })
+    .flat_map(|person| expand_group(person, groups))
     .collect::<HashSet<Person>>();
   let mut person_to_scene_and_schedule_entry = vec![];
   for person in all_persons {
     let schedule_entries_for_person = schedule_to_scene_entries
       .iter()
-      .filter(|(_, scene_entry)| {
+      .filter(|(schedule_entry, scene_entry)| {
         if let Some(scene_entry) = scene_entry {
-          scene_entry.who == person
+          called_persons(schedule_entry, scene_entry, groups).contains(&person)
         } else {
           // if no scene entry this means that all scenes will be played
-          true
+          schedule_entry.called != Called::Crew
         }
       })
       .collect();
@@ -59,6 +94,341 @@ pub fn get_person_to_scene_and_schedule_entry<'a>(
   person_to_scene_and_schedule_entry
 }
 
+/// Everyone who is called for a role on the date of the schedule entry,
+/// including observing understudies.
+fn called_persons(
+  schedule_entry: &ScheduleEntry,
+  scene_entry: &SceneEntry,
+  groups: &[Group],
+) -> Vec<Person> {
+  scene_entry
+    .called_cast(schedule_entry.date)
+    .into_iter()
+    .chain(scene_entry.observing())
+    .flat_map(|person| expand_group(person, groups))
+    .collect()
+}
+
+/// Adds the rotation and understudies of the castings to the scene entries
+/// with the same role. Alternating rotations are resolved into explicit
+/// dates, based on the schedule entries rehearsing the scenes of the role.
+pub fn apply_castings(
+  scene_entries: &mut [SceneEntry],
+  castings: &[Casting],
+  schedule_entries: &[ScheduleEntry],
+) {
+  for scene_entry in scene_entries.iter_mut() {
+    let Some(casting) = castings.iter().find(|c| c.role == scene_entry.role) else {
+      continue;
+    };
+    scene_entry.understudies = casting.understudies.clone();
+    scene_entry.understudies_observe = casting.understudies_observe;
+    scene_entry.rotation = match &casting.rotation {
+      Rotation::Alternating => {
+        let mut dates = schedule_entries
+          .iter()
+          .filter(|schedule_entry| match &schedule_entry.scenes {
+            Scenes::Normal(scenes) => scenes.iter().any(|s| scene_entry.scenes.contains(s)),
+            Scenes::Special(_) => false,
+          })
+          .map(|schedule_entry| schedule_entry.date)
+          .collect::<Vec<_>>();
+        dates.sort_unstable();
+        dates.dedup();
+        let cast = scene_entry.cast().into_iter().cloned().collect::<Vec<_>>();
+        CastRotation::Dates(
+          dates
+            .into_iter()
+            .zip(cast.iter().cycle())
+            .map(|(date, person)| CastDate {
+              date,
+              person: person.clone(),
+            })
+            .collect(),
+        )
+      }
+      Rotation::BothAttend => CastRotation::BothAttend,
+      Rotation::Dates(cast_dates) => CastRotation::Dates(cast_dates.clone()),
+    };
+  }
+}
+
+/// Entries without a stop time end when the next timed entry of the same day
+/// starts, if the config lets their type do so. The stop time is marked as
+/// inferred.
+pub fn infer_stop_times(
+  schedule_entries: Vec<ScheduleEntry>,
+  config: &Config,
+) -> Vec<ScheduleEntry> {
+  let next_starts = (0..schedule_entries.len())
+    .map(|i| {
+      let schedule_entry = &schedule_entries[i];
+      schedule_entries[i + 1..]
+        .iter()
+        .find(|next| !next.is_all_day() && next.date == schedule_entry.date)
+        .map(|next| next.start_stop_time.0)
+    })
+    .collect::<Vec<_>>();
+  schedule_entries
+    .into_iter()
+    .zip(next_starts)
+    .map(|(schedule_entry, next_start)| match next_start {
+      Some(next_start)
+        if !schedule_entry.is_all_day()
+          && schedule_entry.start_stop_time.1.is_none()
+          && schedule_entry.start_stop_time.0 < next_start
+          && config.ends_at_next_entry(schedule_entry.entry_type) =>
+      {
+        schedule_entry.with_inferred_stop_time(next_start)
+      }
+      _ => schedule_entry,
+    })
+    .collect()
+}
+
+fn name_key(name: &str) -> String {
+  name
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+    .to_lowercase()
+}
+
+/// Looks up a person by name or alias. Case and whitespace are ignored.
+pub fn find_person_info<'a>(name: &str, people: &'a [PersonInfo]) -> Option<&'a PersonInfo> {
+  let key = name_key(name);
+  people.iter().find(|info| {
+    name_key(&info.name) == key || info.aliases.iter().any(|alias| name_key(alias) == key)
+  })
+}
+
+/// The type with the given name or keyword, e.g. "Hauptprobe" or
+/// "dress rehearsal". Case and whitespace are ignored.
+pub fn find_entry_type(name: &str, entry_categories: &[EntryCategory]) -> Option<EntryType> {
+  let key = name_key(name);
+  let named = entry_categories.iter().find(|category| {
+    category
+      .names
+      .iter()
+      .any(|category_name| name_key(category_name) == key)
+  });
+  match named {
+    Some(category) => Some(category.entry_type),
+    None => EntryType::ALL
+      .into_iter()
+      .find(|entry_type| entry_type.keyword() == key),
+  }
+}
+
+/// Looks up the venue of a room label. Case and whitespace are ignored.
+pub fn find_venue<'a>(room: &str, venues: &'a [Venue]) -> Option<&'a Venue> {
+  let key = name_key(room);
+  venues.iter().find(|venue| name_key(&venue.room) == key)
+}
+
+/// The name of the people directory for a name or alias. Unknown names are
+/// returned unchanged.
+pub fn canonical_name(name: &str, people: &[PersonInfo]) -> Person {
+  find_person_info(name, people).map_or_else(|| name.to_owned(), |info| info.name.clone())
+}
+
+/// Replaces every name in the scene plan, the groups and the crew by its
+/// canonical name, so that different spellings end up in the same calendar.
+pub fn normalize_names(
+  scene_entries: &mut [SceneEntry],
+  groups: &mut [Group],
+  crew: &mut [CrewMember],
+  people: &[PersonInfo],
+) {
+  if people.is_empty() {
+    return;
+  }
+  let normalize = |name: &mut Person| *name = canonical_name(name, people);
+  for scene_entry in scene_entries {
+    normalize(&mut scene_entry.who);
+    scene_entry.alternates.iter_mut().for_each(normalize);
+    scene_entry.understudies.iter_mut().for_each(normalize);
+    if let CastRotation::Dates(cast_dates) = &mut scene_entry.rotation {
+      for cast_date in cast_dates {
+        normalize(&mut cast_date.person);
+      }
+    }
+  }
+  for group in groups {
+    group.members.iter_mut().for_each(normalize);
+  }
+  for crew_member in crew {
+    normalize(&mut crew_member.name);
+  }
+}
+
+/// Adds the schedule entries of every crew member according to their rules.
+/// The entries are taken from `schedule_entries_without_scene`, which should
+/// contain every schedule entry without a scene entry. If a crew member also
+/// plays a role, the missing entries are added to their existing list.
+pub fn add_crew_to_person_entries<'a>(
+  person_to_scene_and_schedule_entry: &mut PersonToSceneAndScheduleEntry<'a>,
+  crew: &[CrewMember],
+  schedule_entries_without_scene: &'a [(&'a ScheduleEntry, Option<&'a SceneEntry>)],
+  groups: &[Group],
+  default_location: &str,
+) {
+  for crew_member in crew {
+    for name in expand_group(&crew_member.name, groups) {
+      add_crew_entries(
+        person_to_scene_and_schedule_entry,
+        name,
+        &crew_member.rules,
+        schedule_entries_without_scene,
+        default_location,
+      );
+    }
+  }
+}
+
+fn add_crew_entries<'a>(
+  person_to_scene_and_schedule_entry: &mut PersonToSceneAndScheduleEntry<'a>,
+  name: Person,
+  rules: &[CrewRule],
+  schedule_entries_without_scene: &'a [(&'a ScheduleEntry, Option<&'a SceneEntry>)],
+  default_location: &str,
+) {
+  let crew_entries = schedule_entries_without_scene
+    .iter()
+    .filter(|(schedule_entry, _)| {
+      rules
+        .iter()
+        .all(|rule| rule.matches(schedule_entry, default_location))
+    });
+  match person_to_scene_and_schedule_entry
+    .iter_mut()
+    .find(|(person, _)| *person == name)
+  {
+    Some((_, entries)) => {
+      for crew_entry in crew_entries {
+        if !entries
+          .iter()
+          .any(|(schedule_entry, _)| std::ptr::eq(*schedule_entry, crew_entry.0))
+        {
+          entries.push(crew_entry);
+        }
+      }
+      entries.sort_by_key(|(schedule_entry, _)| schedule_entry.start_stop_date_time().0);
+    }
+    None => person_to_scene_and_schedule_entry.push((name, crew_entries.collect())),
+  }
+}
+
+/// Everyone who is called to a schedule entry, keyed by the uuid of the
+/// entry. A person with several roles in an entry is listed once; being
+/// called to play wins over being called to observe.
+pub fn get_called_persons(
+  person_to_scene_and_schedule_entry: &PersonToSceneAndScheduleEntry,
+) -> HashMap<md5::Digest, Vec<(Person, CallType)>> {
+  let mut called_persons: HashMap<md5::Digest, Vec<(Person, CallType)>> = HashMap::new();
+  for (person, entries) in person_to_scene_and_schedule_entry {
+    for (schedule_entry, scene_entry) in entries {
+      let call_type = schedule_entry.call_type_for(*scene_entry, person);
+      let persons = called_persons.entry(schedule_entry.uuid).or_default();
+      match persons.iter_mut().find(|(called, _)| called == person) {
+        Some((_, existing)) if *existing == CallType::Observe => *existing = call_type,
+        Some(_) => {}
+        None => persons.push((person.clone(), call_type)),
+      }
+    }
+  }
+  called_persons
+}
+
+/// Everyone who is called to each schedule entry together with the scene
+/// entry they are called for, sorted by the start of the entry.
+pub fn get_schedule_entry_to_persons<'a>(
+  person_to_scene_and_schedule_entry: &'a PersonToSceneAndScheduleEntry<'a>,
+) -> ScheduleEntryToPersons<'a> {
+  let mut schedule_entry_to_persons: Vec<(&ScheduleEntry, Vec<_>)> = vec![];
+  for (person, entries) in person_to_scene_and_schedule_entry {
+    for (schedule_entry, scene_entry) in entries {
+      let position = schedule_entry_to_persons
+        .iter()
+        .position(|(entry, _)| std::ptr::eq(*entry, *schedule_entry));
+      let persons = match position {
+        Some(position) => &mut schedule_entry_to_persons[position].1,
+        None => {
+          schedule_entry_to_persons.push((*schedule_entry, vec![]));
+          &mut schedule_entry_to_persons.last_mut().unwrap().1
+        }
+      };
+      persons.push((person, *scene_entry));
+    }
+  }
+  schedule_entry_to_persons
+    .sort_by_key(|(schedule_entry, _)| schedule_entry.start_stop_date_time().0);
+  schedule_entry_to_persons
+}
+
+/// Collects for every schedule entry the scene entries which are called. If
+/// no scenes are known for an entry, the whole cast is called, nobody of the
+/// cast if only the crew is.
+pub fn get_schedule_entry_to_cast<'a>(
+  schedule_to_scene_entries: &[(&'a ScheduleEntry, Option<&'a SceneEntry>)],
+  scene_entries: &'a [SceneEntry],
+) -> ScheduleEntryToCast<'a> {
+  let mut schedule_entry_to_cast: ScheduleEntryToCast = vec![];
+  for (schedule_entry, scene_entry) in schedule_to_scene_entries {
+    let index = match schedule_entry_to_cast
+      .iter()
+      .position(|(entry, _)| std::ptr::eq(*entry, *schedule_entry))
+    {
+      Some(index) => index,
+      None => {
+        schedule_entry_to_cast.push((schedule_entry, vec![]));
+        schedule_entry_to_cast.len() - 1
+      }
+    };
+    let cast = &mut schedule_entry_to_cast[index].1;
+    match scene_entry {
+      Some(scene_entry) => cast.push(scene_entry),
+      None if schedule_entry.called == Called::Crew => (),
+      None => cast.extend(scene_entries.iter()),
+    }
+  }
+  schedule_entry_to_cast
+}
+
+/// Keeps only the schedule entries which explicitly rehearse one of the given
+/// scenes, together with the cast playing in them.
+pub fn filter_by_scenes<'a>(
+  schedule_entry_to_cast: &ScheduleEntryToCast<'a>,
+  scenes: &[Scene],
+) -> ScheduleEntryToCast<'a> {
+  schedule_entry_to_cast
+    .iter()
+    .filter(|(schedule_entry, _)| match &schedule_entry.scenes {
+      Scenes::Normal(entry_scenes) => entry_scenes.iter().any(|s| scenes.contains(s)),
+      Scenes::Special(_) => false,
+    })
+    .map(|(schedule_entry, cast)| {
+      let cast_in_scenes = cast
+        .iter()
+        .filter(|scene_entry| scene_entry.scenes.iter().any(|s| scenes.contains(s)))
+        .copied()
+        .collect();
+      (*schedule_entry, cast_in_scenes)
+    })
+    .collect()
+}
+
+/// All scenes of the scene plan in the order they first appear.
+pub fn get_all_scenes(scene_entries: &[SceneEntry]) -> Vec<Scene> {
+  let mut all_scenes: Vec<Scene> = vec![];
+  for scene in scene_entries.iter().flat_map(|entry| &entry.scenes) {
+    if !all_scenes.contains(scene) {
+      all_scenes.push(scene.clone());
+    }
+  }
+  all_scenes
+}
+
 pub fn filter_by_silent_play<'a>(
   schedule_to_scene_entries: &'a Vec<(&ScheduleEntry, Option<&SceneEntry>)>,
   mandatory_silent_play: &'a NaiveDate,
@@ -139,34 +509,34 @@ mod tests {
       ),
     ];
     let scene_entries = vec![
-      SceneEntry {
-        role: "Role 1".to_string(),
-        who: "Person 1".to_string(),
-        scenes: vec![
+      SceneEntry::new(
+        "Role 1".to_string(),
+        "Person 1".to_string(),
+        vec![
           "Scene 1".to_string(),
           "Scene 2".to_string(),
           "Scene 5".to_string(),
         ],
-        silent_play: vec![false, true, false],
-      },
-      SceneEntry {
-        role: "Role 2".to_string(),
-        who: "Person 2".to_string(),
-        scenes: vec!["Scene 3".to_string()],
-        silent_play: vec![true],
-      },
-      SceneEntry {
-        role: "Role 3".to_string(),
-        who: "Person 2".to_string(),
-        scenes: vec!["Scene 3".to_string(), "Scene 5".to_string()],
-        silent_play: vec![true, true],
-      },
-      SceneEntry {
-        role: "Role 4".to_string(),
-        who: "Person 3".to_string(),
-        scenes: vec!["Scene 4".to_string()],
-        silent_play: vec![true],
-      },
+        vec![false, true, false],
+      ),
+      SceneEntry::new(
+        "Role 2".to_string(),
+        "Person 2".to_string(),
+        vec!["Scene 3".to_string()],
+        vec![true],
+      ),
+      SceneEntry::new(
+        "Role 3".to_string(),
+        "Person 2".to_string(),
+        vec!["Scene 3".to_string(), "Scene 5".to_string()],
+        vec![true, true],
+      ),
+      SceneEntry::new(
+        "Role 4".to_string(),
+        "Person 3".to_string(),
+        vec!["Scene 4".to_string()],
+        vec![true],
+      ),
     ];
     (schedule_entries, scene_entries)
   }
@@ -200,7 +570,7 @@ mod tests {
     let (schedule_entries, scene_entries) = test_data();
     let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
     let person_to_scene_and_schedule_entry =
-      get_person_to_scene_and_schedule_entry(&schedule_to_scene_entries);
+      get_person_to_scene_and_schedule_entry(&schedule_to_scene_entries, &[]);
     assert_eq!(
       person_to_scene_and_schedule_entry.len(),
       3,
@@ -229,6 +599,245 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_get_called_persons() {
+    let (schedule_entries, scene_entries) = test_data();
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let person_to_scene_and_schedule_entry =
+      get_person_to_scene_and_schedule_entry(&schedule_to_scene_entries, &[]);
+    let called_persons = get_called_persons(&person_to_scene_and_schedule_entry);
+    assert_eq!(
+      called_persons[&schedule_entries[0].uuid],
+      vec![("Person 2".to_string(), CallType::SilentPlay)],
+      "Person 2 has two roles in scene 3 but should be called once"
+    );
+    assert_eq!(called_persons[&schedule_entries[2].uuid].len(), 3);
+  }
+
+  #[test]
+  fn test_get_schedule_entry_to_persons() {
+    let (schedule_entries, scene_entries) = test_data();
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let person_to_scene_and_schedule_entry =
+      get_person_to_scene_and_schedule_entry(&schedule_to_scene_entries, &[]);
+    let schedule_entry_to_persons =
+      get_schedule_entry_to_persons(&person_to_scene_and_schedule_entry);
+    let dates = schedule_entry_to_persons
+      .iter()
+      .map(|(schedule_entry, _)| schedule_entry.date)
+      .collect::<Vec<_>>();
+    let mut sorted_dates = dates.clone();
+    sorted_dates.sort();
+    assert_eq!(dates, sorted_dates, "Entries should be sorted by start");
+    let (_, persons) = schedule_entry_to_persons
+      .iter()
+      .find(|(schedule_entry, _)| std::ptr::eq(*schedule_entry, &schedule_entries[0]))
+      .unwrap();
+    let roles = persons
+      .iter()
+      .map(|(person, scene_entry)| (person.as_str(), scene_entry.unwrap().role.as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(roles, vec![("Person 2", "Role 2"), ("Person 2", "Role 3")]);
+  }
+
+  #[test]
+  fn test_get_schedule_entry_to_cast() {
+    let (schedule_entries, scene_entries) = test_data();
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let schedule_entry_to_cast =
+      get_schedule_entry_to_cast(&schedule_to_scene_entries, &scene_entries);
+    assert_eq!(
+      schedule_entry_to_cast.len(),
+      4,
+      "Should have one entry per schedule entry",
+    );
+    let cast_sizes = schedule_entry_to_cast
+      .iter()
+      .map(|(_, cast)| cast.len())
+      .collect::<Vec<_>>();
+    assert_eq!(cast_sizes, vec![2, 3, 4, 4]);
+  }
+
+  #[test]
+  fn test_filter_by_scenes() {
+    let (schedule_entries, scene_entries) = test_data();
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let schedule_entry_to_cast =
+      get_schedule_entry_to_cast(&schedule_to_scene_entries, &scene_entries);
+    let scene_5 = filter_by_scenes(&schedule_entry_to_cast, &["Scene 5".to_string()]);
+    assert_eq!(scene_5.len(), 1, "Only one entry rehearses scene 5");
+    let roles = scene_5[0]
+      .1
+      .iter()
+      .map(|scene_entry| scene_entry.role.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(roles, vec!["Role 1", "Role 3"]);
+  }
+
+  #[test]
+  fn test_get_all_scenes() {
+    let (_, scene_entries) = test_data();
+    assert_eq!(
+      get_all_scenes(&scene_entries),
+      vec!["Scene 1", "Scene 2", "Scene 5", "Scene 3", "Scene 4"]
+    );
+  }
+
+  #[test]
+  fn test_add_crew_to_person_entries() {
+    let (schedule_entries, _) = test_data();
+    let schedule_entries_without_scene = schedule_entries
+      .iter()
+      .map(|schedule_entry| (schedule_entry, None))
+      .collect::<Vec<_>>();
+    let crew = vec![
+      CrewMember {
+        name: "Light".to_string(),
+        rules: vec![CrewRule::All],
+      },
+      CrewMember {
+        name: "Sound".to_string(),
+        rules: vec![
+          CrewRule::Room("Default".to_string()),
+          CrewRule::From(NaiveDate::from_ymd_opt(2022, 6, 1).unwrap()),
+        ],
+      },
+      CrewMember {
+        name: "Director".to_string(),
+        rules: vec![CrewRule::Special(None)],
+      },
+    ];
+    let mut person_to_scene_and_schedule_entry = vec![];
+    add_crew_to_person_entries(
+      &mut person_to_scene_and_schedule_entry,
+      &crew,
+      &schedule_entries_without_scene,
+      &[],
+      "Default",
+    );
+    let entry_counts = person_to_scene_and_schedule_entry
+      .iter()
+      .map(|(person, entries)| (person.as_str(), entries.len()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      entry_counts,
+      vec![("Light", 4), ("Sound", 2), ("Director", 0)]
+    );
+  }
+
+  #[test]
+  fn test_get_person_to_scene_and_schedule_entry_expands_groups() {
+    let (schedule_entries, scene_entries) = test_data();
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let groups = vec![
+      Group {
+        name: "Person 2".to_string(),
+        members: vec!["Child 1".to_string(), "Kids".to_string()],
+      },
+      Group {
+        name: "Kids".to_string(),
+        members: vec!["Child 2".to_string(), "Person 2".to_string()],
+      },
+    ];
+    let mut persons = get_person_to_scene_and_schedule_entry(&schedule_to_scene_entries, &groups)
+      .into_iter()
+      .map(|(person, entries)| (person, entries.len()))
+      .collect::<Vec<_>>();
+    persons.sort();
+    assert_eq!(
+      persons,
+      vec![
+        ("Child 1".to_string(), 5),
+        ("Child 2".to_string(), 5),
+        ("Person 1".to_string(), 3),
+        ("Person 3".to_string(), 3),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_apply_castings() {
+    let (schedule_entries, mut scene_entries) = test_data();
+    scene_entries[0].alternates = vec!["Person 4".to_string()];
+    scene_entries[2].alternates = vec!["Person 5".to_string()];
+    let castings = vec![
+      Casting {
+        role: "Role 1".to_string(),
+        rotation: Rotation::Alternating,
+        understudies: vec![],
+        understudies_observe: false,
+      },
+      Casting {
+        role: "Role 3".to_string(),
+        rotation: Rotation::Dates(vec![CastDate {
+          date: NaiveDate::from_ymd_opt(2022, 7, 1).unwrap(),
+          person: "Person 5".to_string(),
+        }]),
+        understudies: vec!["Person 6".to_string()],
+        understudies_observe: true,
+      },
+    ];
+    apply_castings(&mut scene_entries, &castings, &schedule_entries);
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let person_to_scene_and_schedule_entry =
+      get_person_to_scene_and_schedule_entry(&schedule_to_scene_entries, &[]);
+    let entries_of = |person: &str| {
+      person_to_scene_and_schedule_entry
+        .iter()
+        .find(|(p, _)| p == person)
+        .map(|(_, entries)| {
+          entries
+            .iter()
+            .map(|(schedule_entry, scene_entry)| {
+              (
+                schedule_entry.date.to_string(),
+                schedule_entry.call_type_for(*scene_entry, person),
+              )
+            })
+            .collect::<Vec<_>>()
+        })
+    };
+    let date = |d: &str| d.to_string();
+    // Role 1 is only rehearsed on 2022-07-01, which is the first date of
+    // the rotation.
+    assert_eq!(
+      entries_of("Person 1").unwrap()[0],
+      (date("2022-07-01"), CallType::Scenes)
+    );
+    // The alternate of role 1 is only called to the entries with everyone.
+    assert_eq!(entries_of("Person 4").map(|e| e.len()), Some(2));
+    assert_eq!(
+      entries_of("Person 5").unwrap()[0],
+      (date("2022-07-01"), CallType::SilentPlay)
+    );
+    assert_eq!(
+      entries_of("Person 6").unwrap()[..2],
+      [
+        (date("2022-05-01"), CallType::Observe),
+        (date("2022-07-01"), CallType::Observe)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_normalize_names() {
+    let (_, mut scene_entries) = test_data();
+    let mut groups = vec![Group {
+      name: "Kids".to_string(),
+      members: vec!["person  1".to_string()],
+    }];
+    let people = vec![PersonInfo {
+      name: "Person One".to_string(),
+      aliases: vec!["Person 1".to_string()],
+      email: None,
+      phone: None,
+    }];
+    normalize_names(&mut scene_entries, &mut groups, &mut [], &people);
+    assert_eq!(scene_entries[0].who, "Person One");
+    assert_eq!(scene_entries[1].who, "Person 2");
+    assert_eq!(groups[0].members, vec!["Person One"]);
+  }
+
   #[test]
   fn test_filter_by_silent_play() {
     let (schedule_entries, scene_entries) = test_data();
@@ -242,4 +851,129 @@ mod tests {
       "Should have 5 entries for each scene",
     );
   }
+
+  #[test]
+  fn test_entry_categories_decide_who_is_called() {
+    let (mut schedule_entries, scene_entries) = test_data();
+    schedule_entries[0] = ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      Scenes::Normal(vec!["Scene 3".to_string()]),
+      None,
+      None,
+    )
+    .with_type(EntryType::DressRehearsal);
+    schedule_entries[1] = ScheduleEntry::new(
+      NaiveDate::from_ymd_opt(2022, 7, 1).unwrap(),
+      (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      Scenes::Special("Technik".to_string()),
+      None,
+      None,
+    )
+    .with_type(EntryType::Meeting);
+    let mut entry_categories = EntryCategory::defaults();
+    entry_categories[3].called = Called::Crew;
+    apply_entry_categories(&mut schedule_entries, &entry_categories);
+    let schedule_to_scene_entries = get_schedule_to_scene_entry(&schedule_entries, &scene_entries);
+    let calls = |index: usize| {
+      schedule_to_scene_entries
+        .iter()
+        .filter(|(schedule_entry, _)| std::ptr::eq(*schedule_entry, &schedule_entries[index]))
+        .map(|(_, scene_entry)| scene_entry.is_some())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(calls(0), vec![false], "Everyone is called to scene 3");
+    assert_eq!(
+      schedule_entries[0].call_type(None),
+      CallType::Everyone,
+      "Not only the cast of scene 3"
+    );
+    assert_eq!(calls(1), vec![false], "The meeting is kept");
+    let person_to_scene_and_schedule_entry =
+      get_person_to_scene_and_schedule_entry(&schedule_to_scene_entries, &[]);
+    assert!(
+      person_to_scene_and_schedule_entry
+        .iter()
+        .flat_map(|(_, entries)| entries)
+        .all(|(schedule_entry, _)| !std::ptr::eq(*schedule_entry, &schedule_entries[1])),
+      "Only the crew is called to meetings"
+    );
+    let schedule_entry_to_cast =
+      get_schedule_entry_to_cast(&schedule_to_scene_entries, &scene_entries);
+    assert!(schedule_entry_to_cast
+      .iter()
+      .any(
+        |(schedule_entry, cast)| std::ptr::eq(*schedule_entry, &schedule_entries[1])
+          && cast.is_empty()
+      ));
+
+    let crew = CrewMember {
+      name: "Director".to_string(),
+      rules: vec![CrewRule::Type(EntryType::Meeting)],
+    };
+    assert!(crew.rules[0].matches(&schedule_entries[1], "Default"));
+    assert!(!crew.rules[0].matches(&schedule_entries[0], "Default"));
+  }
+
+  #[test]
+  fn test_find_entry_type() {
+    let entry_categories = EntryCategory::defaults();
+    assert_eq!(
+      find_entry_type(" hauptprobe", &entry_categories),
+      Some(EntryType::DressRehearsal)
+    );
+    assert_eq!(find_entry_type("Meeting", &[]), Some(EntryType::Meeting));
+    assert_eq!(find_entry_type("Technik", &entry_categories), None);
+  }
+
+  #[test]
+  fn test_infer_stop_times() {
+    let date = NaiveDate::from_ymd_opt(2022, 5, 1).unwrap();
+    let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+    let entry = |hour, scene: &str| {
+      ScheduleEntry::new(
+        date,
+        (time(hour), None),
+        Scenes::Normal(vec![scene.to_string()]),
+        None,
+        None,
+      )
+    };
+    let schedule_entries = || {
+      vec![
+        entry(10, "1"),
+        entry(14, "2").with_type(EntryType::Meeting),
+        ScheduleEntry::new(
+          date,
+          (time(15), None),
+          Scenes::Special("Gastspiel".to_string()),
+          None,
+          None,
+        )
+        .all_day(date),
+        entry(16, "3"),
+        entry(19, "4"),
+      ]
+    };
+    let mut config = Config::default();
+    config
+      .entry_categories
+      .iter_mut()
+      .find(|category| category.entry_type == EntryType::Meeting)
+      .unwrap()
+      .end_at_next_entry = Some(false);
+    let inferred = infer_stop_times(schedule_entries(), &config);
+    assert_eq!(inferred[0].start_stop_time.1, Some(time(14)));
+    assert!(inferred[0].stop_time_inferred);
+    assert_ne!(inferred[0].uuid, entry(10, "1").uuid);
+    assert_eq!(inferred[1].start_stop_time.1, None);
+    assert!(inferred[2].is_all_day());
+    assert_eq!(inferred[3].start_stop_time.1, Some(time(19)));
+    assert_eq!(inferred[4].start_stop_time.1, None);
+    assert!(!inferred[4].stop_time_inferred);
+
+    config.end_at_next_entry = false;
+    let inferred = infer_stop_times(schedule_entries(), &config);
+    assert!(inferred.iter().all(|entry| !entry.stop_time_inferred));
+  }
 }
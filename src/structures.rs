@@ -2,6 +2,8 @@ use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::i18n::{fill, Language, Phrase};
+
 pub type Person = String;
 pub type Scene = String;
 pub type Room = String;
@@ -9,13 +11,196 @@ pub type Note = String;
 pub type Role = String;
 pub type PersonToSceneAndScheduleEntry<'a> =
   Vec<(Person, Vec<&'a (&'a ScheduleEntry, Option<&'a SceneEntry>)>)>;
+pub type ScheduleEntryToCast<'a> = Vec<(&'a ScheduleEntry, Vec<&'a SceneEntry>)>;
+pub type ScheduleEntryToPersons<'a> =
+  Vec<(&'a ScheduleEntry, Vec<(&'a Person, Option<&'a SceneEntry>)>)>;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Scenes {
   Normal(Vec<Scene>),
   Special(Scene),
 }
 
+/// Scenes rehearsed in their own room while other scenes of the same
+/// schedule entry are rehearsed elsewhere, e.g. the choir next door.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+  pub scenes: Vec<Scene>,
+  #[serde(default)]
+  pub room: Option<Room>,
+}
+
+/// What kind of event a schedule entry is. Everything which is not
+/// recognized as another type is a rehearsal.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum EntryType {
+  #[default]
+  Rehearsal,
+  DressRehearsal,
+  Performance,
+  Meeting,
+}
+
+impl EntryType {
+  pub const ALL: [EntryType; 4] = [
+    EntryType::Rehearsal,
+    EntryType::DressRehearsal,
+    EntryType::Performance,
+    EntryType::Meeting,
+  ];
+
+  pub fn label(&self, language: Language) -> &'static str {
+    language.tr(match self {
+      EntryType::Rehearsal => Phrase::EntryRehearsal,
+      EntryType::DressRehearsal => Phrase::EntryDressRehearsal,
+      EntryType::Performance => Phrase::EntryPerformance,
+      EntryType::Meeting => Phrase::EntryMeeting,
+    })
+  }
+
+  /// Name which is recognized in every language, e.g. in the plan format.
+  pub fn keyword(&self) -> &'static str {
+    match self {
+      EntryType::Rehearsal => "rehearsal",
+      EntryType::DressRehearsal => "dress rehearsal",
+      EntryType::Performance => "performance",
+      EntryType::Meeting => "meeting",
+    }
+  }
+}
+
+/// Who is called to the entries of a type.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Called {
+  /// The cast of the scenes, or everyone if no scenes are given.
+  #[default]
+  Scenes,
+  /// The whole cast, even if scenes are given.
+  Everyone,
+  /// Nobody of the cast, only the crew according to their rules.
+  Crew,
+}
+
+/// How the entries of one type are treated, configured once for the whole
+/// production.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryCategory {
+  pub entry_type: EntryType,
+  /// Names in the scenes or the type column which mark an entry as this
+  /// type, e.g. "Hauptprobe". Case is ignored.
+  #[serde(default)]
+  pub names: Vec<String>,
+  #[serde(default)]
+  pub called: Called,
+  /// Length in minutes of entries without an end time, instead of the one of
+  /// the production.
+  #[serde(default)]
+  pub default_duration: Option<u32>,
+  /// Whether entries without an end time end when the next entry of the day
+  /// starts, instead of the setting of the production.
+  #[serde(default)]
+  pub end_at_next_entry: Option<bool>,
+  /// Template for the event summary instead of the one of the event
+  /// templates.
+  #[serde(default)]
+  pub summary: Option<String>,
+  /// Value of `CATEGORIES` in the calendars. Without it, the translated name
+  /// of the type is used.
+  #[serde(default)]
+  pub category: Option<String>,
+  /// Reminders in minutes before the start.
+  #[serde(default)]
+  pub reminders: Vec<u32>,
+  /// CSS color, e.g. `orange` or `#d33`, used in the calendars and on the
+  /// website.
+  #[serde(default)]
+  pub color: Option<String>,
+}
+
+impl EntryCategory {
+  fn new(entry_type: EntryType, called: Called, names: &[&str], color: Option<&str>) -> Self {
+    Self {
+      entry_type,
+      names: names.iter().map(|name| name.to_string()).collect(),
+      called,
+      default_duration: None,
+      end_at_next_entry: None,
+      summary: None,
+      category: None,
+      reminders: vec![],
+      color: color.map(str::to_owned),
+    }
+  }
+
+  /// The types with the names commonly used in German, English and French
+  /// schedules. Like plain special entries, all but rehearsals call
+  /// everyone.
+  pub fn defaults() -> Vec<Self> {
+    vec![
+      Self::new(
+        EntryType::Rehearsal,
+        Called::Scenes,
+        &["Probe", "Rehearsal", "Répétition"],
+        None,
+      ),
+      Self::new(
+        EntryType::DressRehearsal,
+        Called::Everyone,
+        &[
+          "Hauptprobe",
+          "Generalprobe",
+          "HP",
+          "GP",
+          "Dress rehearsal",
+          "Répétition générale",
+          "Générale",
+        ],
+        Some("orange"),
+      ),
+      Self::new(
+        EntryType::Performance,
+        Called::Everyone,
+        &[
+          "Premiere",
+          "Vorstellung",
+          "Aufführung",
+          "Dernière",
+          "Performance",
+          "Première",
+          "Représentation",
+        ],
+        Some("crimson"),
+      ),
+      Self::new(
+        EntryType::Meeting,
+        Called::Everyone,
+        &["Besprechung", "Sitzung", "Meeting", "Réunion"],
+        Some("gray"),
+      ),
+    ]
+  }
+}
+
+/// Why a person is called to a schedule entry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum CallType {
+  Scenes,
+  SilentPlay,
+  Everyone,
+  Observe,
+}
+
+impl CallType {
+  pub fn label(&self, language: Language) -> &'static str {
+    language.tr(match self {
+      CallType::Scenes => Phrase::CallScenes,
+      CallType::SilentPlay => Phrase::CallSilentPlay,
+      CallType::Everyone => Phrase::CallEveryone,
+      CallType::Observe => Phrase::CallObserve,
+    })
+  }
+}
+
 #[derive(Error, Debug)]
 pub enum SceneSchedulerError {
   #[error("Error while reading the excel file with calamine: {0}")]
@@ -33,43 +218,324 @@ pub enum SceneSchedulerError {
     row: usize,
     column: usize,
     token: String,
-    expected: String,
+    expected: Phrase,
   },
   #[error("Error while reading the excel file: {file}. {message}")]
   ExcelError {
     file: String,
-    message: String,
+    message: Phrase,
     sheet: String,
   },
-  #[error("Error during writing of the ics files. {0}")]
-  Ics(String),
+  #[error("Error during writing of the ics files. {}", fill(Language::English.tr(*.message), &[("value", .value)]))]
+  Ics { message: Phrase, value: String },
+  #[error("Error while creating or sending the e-mails. {}", fill(Language::English.tr(*.message), &[("value", .value), ("error", .error)]))]
+  Mail {
+    message: Phrase,
+    value: String,
+    error: String,
+  },
+  #[error("Could not start the server. {0}")]
+  Serve(String),
+  #[error("Error while uploading to the CalDAV server. {}", fill(Language::English.tr(*.message), &[("url", .url), ("error", .error)]))]
+  CalDav {
+    message: Phrase,
+    url: String,
+    error: String,
+  },
+  #[error("Error while writing the excel file: {0}")]
+  Xlsx(#[from] rust_xlsxwriter::XlsxError),
+  #[error("Could not import the calendar '{file}'. {message}")]
+  CalendarImport { file: String, message: String },
+  #[error(
+    "The file '{file}' has format version {found}, but only version {supported} is supported."
+  )]
+  ModelVersion {
+    file: String,
+    found: u32,
+    supported: u32,
+  },
+  #[error(
+    "The role '{role}' in the file '{file}' does not have a silent play mark for every scene."
+  )]
+  ModelSilentPlay { file: String, role: Role },
+  #[error("'{first}' and '{second}' would both be written to the file '{file}'.")]
+  FileNameCollision {
+    file: String,
+    first: String,
+    second: String,
+  },
+  #[error("Parsing error for file '{file}' (line {line}, column {column}). {expected} Unexpected token '{token}'.")]
+  PlanParseError {
+    file: String,
+    line: usize,
+    column: usize,
+    token: String,
+    expected: Phrase,
+  },
+}
+
+impl SceneSchedulerError {
+  pub fn localized(&self, language: Language) -> String {
+    match self {
+      SceneSchedulerError::Calamine(error) => {
+        fill(language.tr(Phrase::ErrorCalamine), &[("error", error)])
+      }
+      SceneSchedulerError::Io(error) => fill(language.tr(Phrase::ErrorIo), &[("error", error)]),
+      SceneSchedulerError::Iced(error) => fill(language.tr(Phrase::ErrorIced), &[("error", error)]),
+      SceneSchedulerError::SerdeJson(error) => {
+        fill(language.tr(Phrase::ErrorSerdeJson), &[("error", error)])
+      }
+      SceneSchedulerError::ExcelParseError {
+        file,
+        sheet,
+        row,
+        column,
+        token,
+        expected,
+      } => fill(
+        language.tr(Phrase::ErrorExcelParse),
+        &[
+          ("file", file),
+          ("sheet", sheet),
+          ("row", row),
+          ("column", column),
+          ("expected", &language.tr(*expected)),
+          ("token", token),
+        ],
+      ),
+      SceneSchedulerError::ExcelError { file, message, .. } => fill(
+        language.tr(Phrase::ErrorExcel),
+        &[("file", file), ("message", &language.tr(*message))],
+      ),
+      SceneSchedulerError::Ics { message, value } => fill(
+        language.tr(Phrase::ErrorIcs),
+        &[("error", &fill(language.tr(*message), &[("value", value)]))],
+      ),
+      SceneSchedulerError::Mail {
+        message,
+        value,
+        error,
+      } => fill(
+        language.tr(Phrase::ErrorMail),
+        &[(
+          "error",
+          &fill(language.tr(*message), &[("value", value), ("error", error)]),
+        )],
+      ),
+      SceneSchedulerError::Serve(error) => {
+        fill(language.tr(Phrase::ErrorServe), &[("error", error)])
+      }
+      SceneSchedulerError::CalDav {
+        message,
+        url,
+        error,
+      } => fill(
+        language.tr(Phrase::ErrorCalDav),
+        &[(
+          "error",
+          &fill(language.tr(*message), &[("url", url), ("error", error)]),
+        )],
+      ),
+      SceneSchedulerError::Xlsx(error) => fill(language.tr(Phrase::ErrorXlsx), &[("error", error)]),
+      SceneSchedulerError::CalendarImport { file, message } => fill(
+        language.tr(Phrase::ErrorCalendarImport),
+        &[("file", file), ("message", message)],
+      ),
+      SceneSchedulerError::ModelVersion {
+        file,
+        found,
+        supported,
+      } => fill(
+        language.tr(Phrase::ErrorModelVersion),
+        &[("file", file), ("found", found), ("supported", supported)],
+      ),
+      SceneSchedulerError::ModelSilentPlay { file, role } => fill(
+        language.tr(Phrase::ErrorModelSilentPlay),
+        &[("file", file), ("role", role)],
+      ),
+      SceneSchedulerError::FileNameCollision {
+        file,
+        first,
+        second,
+      } => fill(
+        language.tr(Phrase::ErrorFileNameCollision),
+        &[("file", file), ("first", first), ("second", second)],
+      ),
+      SceneSchedulerError::PlanParseError {
+        file,
+        line,
+        column,
+        token,
+        expected,
+      } => fill(
+        language.tr(Phrase::ErrorPlanParse),
+        &[
+          ("file", file),
+          ("line", line),
+          ("column", column),
+          ("expected", &language.tr(*expected)),
+          ("token", token),
+        ],
+      ),
+    }
+  }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SceneEntry {
   pub role: Role,
   pub who: Person,
+  /// Further cast members if the role is double cast.
+  #[serde(default)]
+  pub alternates: Vec<Person>,
+  #[serde(default)]
+  pub understudies: Vec<Person>,
+  /// Whether the understudies are called to observe the rehearsals.
+  #[serde(default)]
+  pub understudies_observe: bool,
+  #[serde(default)]
+  pub rotation: CastRotation,
   pub scenes: Vec<Scene>,
   pub silent_play: Vec<bool>,
 }
 
 impl SceneEntry {
+  pub fn new(role: Role, who: Person, scenes: Vec<Scene>, silent_play: Vec<bool>) -> Self {
+    Self {
+      role,
+      who,
+      alternates: vec![],
+      understudies: vec![],
+      understudies_observe: false,
+      rotation: CastRotation::default(),
+      scenes,
+      silent_play,
+    }
+  }
+
+  /// All cast members of the role, the first one is the main cast.
+  pub fn cast(&self) -> Vec<&Person> {
+    std::iter::once(&self.who)
+      .chain(self.alternates.iter())
+      .collect()
+  }
+
+  /// The cast members who play the role on the given date.
+  pub fn called_cast(&self, date: NaiveDate) -> Vec<&Person> {
+    match &self.rotation {
+      CastRotation::BothAttend => self.cast(),
+      CastRotation::Dates(cast_dates) => vec![cast_dates
+        .iter()
+        .find(|cast_date| cast_date.date == date)
+        .map_or(&self.who, |cast_date| &cast_date.person)],
+    }
+  }
+
+  /// The understudies who are called to observe.
+  pub fn observing(&self) -> &[Person] {
+    if self.understudies_observe {
+      &self.understudies
+    } else {
+      &[]
+    }
+  }
+
   pub fn is_scene_silent_play(&self, scene: &Scene) -> Option<bool> {
     let index = self.scenes.iter().position(|x| x == scene);
     index.map(|i| self.silent_play[i])
   }
 }
 
-#[derive(Debug)]
+/// The UID is written for reference but computed again when reading, so it
+/// always matches the other fields.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "ScheduleEntryFields")]
 pub struct ScheduleEntry {
   pub date: NaiveDate,
   pub start_stop_time: (NaiveTime, Option<NaiveTime>),
+  /// With parallel sessions, the scenes of all sessions.
   pub scenes: Scenes,
+  /// With parallel sessions, the room of the first session.
   pub room: Option<Room>,
+  /// Empty unless the entry is split into parallel sessions.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub sessions: Vec<Session>,
   pub note: Option<Note>,
+  /// Set for entries without a time. They last whole days from `date` up to
+  /// and including this date.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub all_day_until: Option<NaiveDate>,
+  #[serde(skip_serializing_if = "is_rehearsal")]
+  pub entry_type: EntryType,
+  /// Whether the stop time was not given but inferred, e.g. from the start
+  /// of the next entry.
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub stop_time_inferred: bool,
+  /// Who is called according to the category of the type, taken from the
+  /// config when the model is read.
+  #[serde(skip)]
+  pub called: Called,
+  #[serde(rename = "uid", serialize_with = "serialize_digest")]
   pub uuid: md5::Digest,
 }
 
+#[derive(Deserialize)]
+struct ScheduleEntryFields {
+  date: NaiveDate,
+  start_stop_time: (NaiveTime, Option<NaiveTime>),
+  scenes: Scenes,
+  #[serde(default)]
+  room: Option<Room>,
+  #[serde(default)]
+  sessions: Vec<Session>,
+  #[serde(default)]
+  note: Option<Note>,
+  #[serde(default)]
+  all_day_until: Option<NaiveDate>,
+  #[serde(default)]
+  entry_type: EntryType,
+  #[serde(default)]
+  stop_time_inferred: bool,
+}
+
+impl From<ScheduleEntryFields> for ScheduleEntry {
+  fn from(fields: ScheduleEntryFields) -> Self {
+    let schedule_entry = if fields.sessions.is_empty() {
+      Self::new(
+        fields.date,
+        fields.start_stop_time,
+        fields.scenes,
+        fields.room,
+        fields.note,
+      )
+    } else {
+      Self::with_sessions(
+        fields.date,
+        fields.start_stop_time,
+        fields.sessions,
+        fields.note,
+      )
+    };
+    let mut schedule_entry = match fields.all_day_until {
+      Some(last_date) => schedule_entry.all_day(last_date),
+      None => schedule_entry,
+    };
+    schedule_entry.stop_time_inferred = fields.stop_time_inferred;
+    schedule_entry.with_type(fields.entry_type)
+  }
+}
+
+fn is_rehearsal(entry_type: &EntryType) -> bool {
+  *entry_type == EntryType::Rehearsal
+}
+
+fn serialize_digest<S: serde::Serializer>(
+  digest: &md5::Digest,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.collect_str(&format_args!("{:x}", digest))
+}
+
 impl ScheduleEntry {
   pub fn new(
     date: NaiveDate,
@@ -78,18 +544,234 @@ impl ScheduleEntry {
     room: Option<Room>,
     note: Option<Note>,
   ) -> Self {
-    let uuid = Self::get_uuid(&scenes, &date, &start_stop_time, &room, &note);
+    let uuid = Self::get_uuid(&scenes, &date, &start_stop_time, &room, &[], &note, None);
     Self {
       date,
       start_stop_time,
       scenes,
       room,
+      sessions: vec![],
       note,
+      all_day_until: None,
+      entry_type: EntryType::Rehearsal,
+      stop_time_inferred: false,
+      called: Called::default(),
       uuid,
     }
   }
 
+  /// The type does not change the UID, so that calendars keep the event
+  /// when the type is changed.
+  pub fn with_type(mut self, entry_type: EntryType) -> Self {
+    self.entry_type = entry_type;
+    self
+  }
+
+  /// Turns the entry into one without a time which lasts whole days up to
+  /// and including `last_date`.
+  pub fn all_day(mut self, last_date: NaiveDate) -> Self {
+    self.start_stop_time = (NaiveTime::MIN, None);
+    self.all_day_until = Some(last_date.max(self.date));
+    self.stop_time_inferred = false;
+    self.update_uuid();
+    self
+  }
+
+  /// Sets a stop time which was not given. Like a given one, it is part of
+  /// the UID.
+  pub fn with_inferred_stop_time(mut self, stop_time: NaiveTime) -> Self {
+    self.start_stop_time.1 = Some(stop_time);
+    self.stop_time_inferred = true;
+    self.update_uuid();
+    self
+  }
+
+  fn update_uuid(&mut self) {
+    self.uuid = Self::get_uuid(
+      &self.scenes,
+      &self.date,
+      &self.start_stop_time,
+      &self.room,
+      &self.sessions,
+      &self.note,
+      self.all_day_until,
+    );
+  }
+
+  pub fn is_all_day(&self) -> bool {
+    self.all_day_until.is_some()
+  }
+
+  /// The last day of the entry, which is the first one unless the entry
+  /// spans several days.
+  pub fn last_date(&self) -> NaiveDate {
+    self.all_day_until.unwrap_or(self.date)
+  }
+
+  /// Every day the entry takes place on.
+  pub fn dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+    self
+      .date
+      .iter_days()
+      .take_while(|date| *date <= self.last_date())
+  }
+
+  /// Entry split into sessions taking place at the same time in different
+  /// rooms. A single session is an ordinary entry.
+  pub fn with_sessions(
+    date: NaiveDate,
+    start_stop_time: (NaiveTime, Option<NaiveTime>),
+    mut sessions: Vec<Session>,
+    note: Option<Note>,
+  ) -> Self {
+    if sessions.len() <= 1 {
+      let session = sessions.pop().unwrap_or(Session {
+        scenes: vec![],
+        room: None,
+      });
+      return Self::new(
+        date,
+        start_stop_time,
+        Scenes::Normal(session.scenes),
+        session.room,
+        note,
+      );
+    }
+    let scenes = Scenes::Normal(
+      sessions
+        .iter()
+        .flat_map(|session| session.scenes.iter().cloned())
+        .collect(),
+    );
+    let room = sessions[0].room.clone();
+    let uuid = Self::get_uuid(
+      &scenes,
+      &date,
+      &start_stop_time,
+      &room,
+      &sessions,
+      &note,
+      None,
+    );
+    Self {
+      date,
+      start_stop_time,
+      scenes,
+      room,
+      sessions,
+      note,
+      all_day_until: None,
+      entry_type: EntryType::Rehearsal,
+      stop_time_inferred: false,
+      called: Called::default(),
+      uuid,
+    }
+  }
+
+  /// The session in which the role is rehearsed, `None` without parallel
+  /// sessions or for people not called for a role.
+  pub fn session_for(&self, scene_entry: Option<&SceneEntry>) -> Option<&Session> {
+    let scene_entry = scene_entry?;
+    self.sessions.iter().find(|session| {
+      session
+        .scenes
+        .iter()
+        .any(|scene| scene_entry.scenes.contains(scene))
+    })
+  }
+
+  /// The room a person called for the role has to go to.
+  pub fn room_for(&self, scene_entry: Option<&SceneEntry>) -> Option<&str> {
+    match self.session_for(scene_entry) {
+      Some(session) => session.room.as_deref(),
+      None => self.room.as_deref(),
+    }
+  }
+
+  /// All rooms used at the same time, without duplicates.
+  pub fn rooms<'a>(&'a self, default_location: &'a str) -> Vec<&'a str> {
+    if self.sessions.is_empty() {
+      return vec![self.room.as_deref().unwrap_or(default_location)];
+    }
+    let mut rooms = vec![];
+    for session in &self.sessions {
+      let room = session.room.as_deref().unwrap_or(default_location);
+      if !rooms.contains(&room) {
+        rooms.push(room);
+      }
+    }
+    rooms
+  }
+
+  /// The rooms of all sessions, e.g. for the location of calendars showing
+  /// the whole entry.
+  pub fn location(&self, default_location: &str) -> String {
+    self.rooms(default_location).join(", ")
+  }
+
+  /// Whether `other` is this entry with a stop time, e.g. as it was exported
+  /// after its stop time was inferred.
+  pub fn is_with_stop_time(&self, other: &ScheduleEntry) -> bool {
+    match (self.start_stop_time, other.start_stop_time) {
+      ((start, None), (other_start, Some(stop))) if start == other_start => {
+        Self::get_uuid(
+          &self.scenes,
+          &self.date,
+          &(start, Some(stop)),
+          &self.room,
+          &self.sessions,
+          &self.note,
+          self.all_day_until,
+        ) == other.uuid
+      }
+      _ => false,
+    }
+  }
+
+  pub fn call_type(&self, scene_entry: Option<&SceneEntry>) -> CallType {
+    let (scenes, scene_entry) = match (&self.scenes, scene_entry) {
+      _ if self.called == Called::Everyone => return CallType::Everyone,
+      (Scenes::Special(_), _) => return CallType::Everyone,
+      (Scenes::Normal(scenes), _) if scenes.is_empty() => return CallType::Everyone,
+      (Scenes::Normal(_), None) => return CallType::Scenes,
+      (Scenes::Normal(scenes), Some(scene_entry)) => (scenes, scene_entry),
+    };
+    let only_silent_play = scenes
+      .iter()
+      .filter_map(|scene| scene_entry.is_scene_silent_play(scene))
+      .all(|silent_play| silent_play);
+    if only_silent_play {
+      CallType::SilentPlay
+    } else {
+      CallType::Scenes
+    }
+  }
+
+  /// Like [`ScheduleEntry::call_type`] but also detects understudies who are
+  /// only called to observe.
+  pub fn call_type_for(&self, scene_entry: Option<&SceneEntry>, person: &str) -> CallType {
+    if let Some(scene_entry) = scene_entry {
+      let is_playing = scene_entry
+        .called_cast(self.date)
+        .iter()
+        .any(|cast| *cast == person);
+      if !is_playing && scene_entry.observing().iter().any(|u| u == person) {
+        return CallType::Observe;
+      }
+    }
+    self.call_type(scene_entry)
+  }
+
+  /// All-day entries last until midnight after their last day.
   pub fn start_stop_date_time(&self) -> (NaiveDateTime, Option<NaiveDateTime>) {
+    if let Some(last_date) = self.all_day_until {
+      return (
+        self.date.and_time(NaiveTime::MIN),
+        last_date
+          .succ_opt()
+          .map(|date| date.and_time(NaiveTime::MIN)),
+      );
+    }
     let start_date_time = self.date.and_time(self.start_stop_time.0);
     let stop_date_time = self
       .start_stop_time
@@ -103,7 +785,9 @@ impl ScheduleEntry {
     date: &NaiveDate,
     start_stop_time: &(NaiveTime, Option<NaiveTime>),
     room: &Option<String>,
+    sessions: &[Session],
     note: &Option<String>,
+    all_day_until: Option<NaiveDate>,
   ) -> md5::Digest {
     let mut scenes = match scenes {
       Scenes::Normal(s) => s.clone(),
@@ -126,18 +810,233 @@ impl ScheduleEntry {
       Some(n) => n.to_owned(),
       None => "None".to_owned(),
     };
+    // Left out for entries without sessions, so that their UIDs stay the
+    // same as before sessions existed.
+    let sessions_str = sessions
+      .iter()
+      .map(|session| {
+        format!(
+          "{}@{}",
+          session.scenes.join(","),
+          session.room.as_deref().unwrap_or("None")
+        )
+      })
+      .collect::<Vec<_>>()
+      .join(";");
+    let all_day_str = match all_day_until {
+      Some(last_date) => format!("until{}", last_date.format("%Y-%m-%d")),
+      None => String::new(),
+    };
 
     md5::compute(format!(
-      "{}{}{}{}{}",
+      "{}{}{}{}{}{}{}",
       date_str,
       start_stop_time_str,
       scenes.join(""),
       room_str,
       note_str,
+      sessions_str,
+      all_day_str,
     ))
   }
 }
 
+/// Which cast member of a double cast role is called.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum Rotation {
+  /// All cast members attend every rehearsal.
+  #[default]
+  BothAttend,
+  /// The cast members take turns on the dates the role is rehearsed.
+  Alternating,
+  /// Explicit dates. On dates which are not listed, the main cast plays.
+  Dates(Vec<CastDate>),
+}
+
+/// The rotation of a scene entry. Alternating rotations are resolved into
+/// dates by `apply_castings`, as they depend on the schedule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum CastRotation {
+  #[default]
+  BothAttend,
+  Dates(Vec<CastDate>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastDate {
+  pub date: NaiveDate,
+  pub person: Person,
+}
+
+/// Casting details of a role which are not part of the scene plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Casting {
+  pub role: Role,
+  #[serde(default)]
+  pub rotation: Rotation,
+  #[serde(default)]
+  pub understudies: Vec<Person>,
+  #[serde(default)]
+  pub understudies_observe: bool,
+}
+
+/// Entry of the people directory. Aliases are other spellings of the name
+/// used in the spreadsheet, e.g. "Anna M." for "Anna Müller".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonInfo {
+  pub name: Person,
+  #[serde(default)]
+  pub aliases: Vec<String>,
+  #[serde(default)]
+  pub email: Option<String>,
+  #[serde(default)]
+  pub phone: Option<String>,
+}
+
+/// Entry of the venue directory. Schedule entries and the location of the
+/// plan refer to it by its short label, e.g. "Saal".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Venue {
+  pub room: Room,
+  #[serde(default)]
+  pub address: Option<String>,
+  #[serde(default)]
+  pub building: Option<String>,
+  #[serde(default)]
+  pub floor: Option<String>,
+  #[serde(default)]
+  pub directions: Option<String>,
+  /// Latitude and longitude in degrees.
+  #[serde(default)]
+  pub coordinates: Option<(f64, f64)>,
+}
+
+impl Venue {
+  /// Label and full address, e.g. `Saal, Altbau, 2. OG, Hauptstrasse 1, Zürich`.
+  pub fn location(&self) -> String {
+    [&self.building, &self.floor, &self.address]
+      .into_iter()
+      .flatten()
+      .fold(self.room.clone(), |location, part| {
+        format!("{}, {}", location, part)
+      })
+  }
+}
+
+/// A name used in the scene plan instead of listing every member, e.g. "Chor".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Group {
+  pub name: String,
+  pub members: Vec<Person>,
+}
+
+/// Resolves a person or group name to all persons it stands for. Groups may
+/// contain other groups.
+pub fn expand_group(name: &str, groups: &[Group]) -> Vec<Person> {
+  fn expand(name: &str, groups: &[Group], visited: &mut Vec<String>, persons: &mut Vec<Person>) {
+    match groups.iter().find(|group| group.name == name) {
+      Some(group) if !visited.contains(&group.name) => {
+        visited.push(group.name.clone());
+        for member in &group.members {
+          expand(member, groups, visited, persons);
+        }
+      }
+      Some(_) => {}
+      None => {
+        if !persons.iter().any(|person| person == name) {
+          persons.push(name.to_owned());
+        }
+      }
+    }
+  }
+  let mut persons = vec![];
+  expand(name, groups, &mut vec![], &mut persons);
+  persons
+}
+
+/// A user defined set of scenes which get a common calendar, e.g. "Akt 1".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneGroup {
+  pub name: String,
+  pub scenes: Vec<Scene>,
+}
+
+/// Decides to which schedule entries a crew member is called.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CrewRule {
+  /// Every schedule entry.
+  All,
+  /// Only entries taking place in this room.
+  Room(Room),
+  /// Only special entries like "Hauptprobe". If a name is given, only the
+  /// special entries with this name.
+  Special(Option<Scene>),
+  /// Only entries on or after this date.
+  From(NaiveDate),
+  /// Only entries of this type, e.g. meetings.
+  Type(EntryType),
+}
+
+impl CrewRule {
+  pub fn matches(&self, schedule_entry: &ScheduleEntry, default_location: &str) -> bool {
+    match self {
+      CrewRule::All => true,
+      CrewRule::Room(room) => schedule_entry
+        .rooms(default_location)
+        .contains(&room.as_str()),
+      CrewRule::Special(name) => match &schedule_entry.scenes {
+        Scenes::Special(scene) => match name {
+          Some(name) => name == scene,
+          None => true,
+        },
+        Scenes::Normal(_) => false,
+      },
+      CrewRule::From(date) => schedule_entry.date >= *date,
+      CrewRule::Type(entry_type) => schedule_entry.entry_type == *entry_type,
+    }
+  }
+}
+
+/// A member of the crew or production team (e.g. lighting, sound, director).
+/// They are called to all entries matching every one of their rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewMember {
+  pub name: Person,
+  pub rules: Vec<CrewRule>,
+}
+
+/// How the entries of an imported calendar are combined with the schedule
+/// sheet.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum ImportMode {
+  /// Added to the entries of the schedule sheet. Entries which are already
+  /// there are not added twice.
+  #[default]
+  Merge,
+  /// Used instead of the entries of the schedule sheet.
+  Replace,
+}
+
+/// An `.ics` file, e.g. the booking calendar of a venue, whose events are
+/// read as schedule entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarImport {
+  pub path: String,
+  #[serde(default)]
+  pub mode: ImportMode,
+  /// Regular expression for the summary. The group `scenes`, or the whole
+  /// match, is read like the scenes column of the schedule sheet. Events
+  /// whose summary does not match are skipped. Without a pattern, the whole
+  /// summary is used.
+  #[serde(default)]
+  pub summary_pattern: Option<String>,
+  /// Regular expression for the description. The group `note`, or the whole
+  /// match, becomes the note. Without a pattern, the whole description is
+  /// used.
+  #[serde(default)]
+  pub description_pattern: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum ThemeType {
   Light,
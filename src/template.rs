@@ -0,0 +1,341 @@
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{Language, Phrase};
+use crate::structures::{SceneEntry, Scenes, ScheduleEntry};
+
+const CALL_DATE_FORMAT: &str = "%d.%m.%Y %H:%M";
+const CALL_DAY_FORMAT: &str = "%d.%m.%Y";
+const STOP_TIME_FORMAT: &str = "%H:%M";
+/// Put in front of stop times which were inferred instead of given.
+const ESTIMATED_MARK: &str = "~";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventTemplates {
+  pub summary: String,
+  pub description: String,
+}
+
+impl EventTemplates {
+  pub fn for_language(language: Language) -> Self {
+    Self {
+      summary: String::from("{production}"),
+      description: format!(
+        "{}: {{role}}\n{}: {{scenes}}\n{{special}}\n{}: {{sessions}}\n{}: {{note}}\n{}: {{cast}}\n{}: {{directions}}\n{{estimated_end}}\n",
+        language.tr(Phrase::RoleLabel),
+        language.tr(Phrase::ScenesLabel),
+        language.tr(Phrase::SessionsLabel),
+        language.tr(Phrase::NoteLabel),
+        language.tr(Phrase::CastLabel),
+        language.tr(Phrase::DirectionsLabel),
+      ),
+    }
+  }
+}
+
+/// Values which can be inserted into an event template. Empty values are
+/// treated as missing.
+#[derive(Debug, Default)]
+pub struct EventContext {
+  pub production: String,
+  pub person: String,
+  pub role: String,
+  /// The scenes which are rehearsed, empty for special entries.
+  pub scenes: String,
+  /// The name of a special entry, e.g. "Hauptprobe".
+  pub special: String,
+  pub room: String,
+  /// The translated type of the entry, e.g. "Hauptprobe".
+  pub entry_type: String,
+  /// The parallel sessions with their rooms, empty if there are none.
+  pub sessions: String,
+  /// How to find the room, from the venue directory.
+  pub directions: String,
+  pub note: String,
+  pub call: String,
+  pub cast: String,
+  pub email: String,
+  pub phone: String,
+  /// A hint that the end of the event is only estimated, empty if it was
+  /// given.
+  pub estimated_end: String,
+}
+
+impl EventContext {
+  fn value(&self, placeholder: &str) -> Option<&str> {
+    let value = match placeholder {
+      "production" => &self.production,
+      "person" => &self.person,
+      "role" => &self.role,
+      "scenes" => &self.scenes,
+      "special" => &self.special,
+      "room" => &self.room,
+      "type" => &self.entry_type,
+      "sessions" => &self.sessions,
+      "directions" => &self.directions,
+      "note" => &self.note,
+      "call" => &self.call,
+      "cast" => &self.cast,
+      "email" => &self.email,
+      "phone" => &self.phone,
+      "estimated_end" => &self.estimated_end,
+      _ => return None,
+    };
+    Some(value.as_str())
+  }
+}
+
+/// Replaces all `{placeholder}` occurrences in the template. A line which
+/// contains placeholders that are all empty is left out completely, so that
+/// e.g. `Anmerkung: {note}` disappears when there is no note. Unknown
+/// placeholders are kept as they are.
+pub fn render(template: &str, context: &EventContext) -> String {
+  let mut rendered_lines = vec![];
+  for line in template.split('\n') {
+    let mut rendered = String::new();
+    let mut any_placeholder = false;
+    let mut any_value = false;
+    let mut rest = line;
+    while let Some(start) = rest.find('{') {
+      rendered.push_str(&rest[..start]);
+      let after_start = &rest[start + 1..];
+      let value = after_start
+        .find('}')
+        .and_then(|end| context.value(&after_start[..end]).map(|value| (end, value)));
+      match value {
+        Some((end, value)) => {
+          any_placeholder = true;
+          any_value |= !value.is_empty();
+          rendered.push_str(value);
+          rest = &after_start[end + 1..];
+        }
+        None => {
+          rendered.push('{');
+          rest = after_start;
+        }
+      }
+    }
+    rendered.push_str(rest);
+    if !any_placeholder || any_value {
+      rendered_lines.push(rendered);
+    }
+  }
+  rendered_lines.join("\n")
+}
+
+/// The stop time of a timed entry, marked with `~` if it was inferred.
+pub fn format_stop_time(schedule_entry: &ScheduleEntry) -> Option<String> {
+  let stop = schedule_entry.start_stop_time.1?;
+  let mark = if schedule_entry.stop_time_inferred {
+    ESTIMATED_MARK
+  } else {
+    ""
+  };
+  Some(format!("{}{}", mark, stop.format(STOP_TIME_FORMAT)))
+}
+
+/// One line describing a call, e.g. `01.05.2022 10:00–13:00, Probebühne: 3, 4 (Gretchen)`.
+/// All-day entries only show their days, e.g. `12.04.2024–14.04.2024`.
+pub fn format_call(
+  schedule_entry: &ScheduleEntry,
+  scene_entry: Option<&SceneEntry>,
+  default_location: &str,
+) -> String {
+  let mut call = format_days(schedule_entry).unwrap_or_else(|| {
+    let start = schedule_entry.start_stop_date_time().0;
+    let mut call = start.format(CALL_DATE_FORMAT).to_string();
+    if let Some(stop) = format_stop_time(schedule_entry) {
+      call.push_str(&format!("–{}", stop));
+    }
+    call
+  });
+  match scene_entry {
+    Some(_) => call.push_str(&format!(
+      ", {}",
+      schedule_entry
+        .room_for(scene_entry)
+        .unwrap_or(default_location)
+    )),
+    None => call.push_str(&format!(", {}", schedule_entry.location(default_location))),
+  }
+  match (
+    schedule_entry.session_for(scene_entry),
+    &schedule_entry.scenes,
+  ) {
+    (Some(session), _) => call.push_str(&format!(": {}", session.scenes.join(", "))),
+    (None, Scenes::Normal(scenes)) if !scenes.is_empty() => {
+      call.push_str(&format!(": {}", scenes.join(", ")))
+    }
+    (None, Scenes::Special(scene)) => call.push_str(&format!(": {}", scene)),
+    (None, Scenes::Normal(_)) => {}
+  }
+  if let Some(scene_entry) = scene_entry {
+    call.push_str(&format!(" ({})", scene_entry.role));
+  }
+  call
+}
+
+/// The days of an all-day entry, `None` for entries with a time.
+pub fn format_days(schedule_entry: &ScheduleEntry) -> Option<String> {
+  let last_date = schedule_entry.all_day_until?;
+  let mut days = schedule_entry.date.format(CALL_DAY_FORMAT).to_string();
+  if last_date != schedule_entry.date {
+    days.push_str(&format!("–{}", last_date.format(CALL_DAY_FORMAT)));
+  }
+  Some(days)
+}
+
+/// The parallel sessions of the entry, e.g. `Saal: 3, 4; Raum B: 7`.
+/// Empty if the entry is not split.
+pub fn format_sessions(schedule_entry: &ScheduleEntry, default_location: &str) -> String {
+  schedule_entry
+    .sessions
+    .iter()
+    .map(|session| {
+      format!(
+        "{}: {}",
+        session.room.as_deref().unwrap_or(default_location),
+        session.scenes.join(", ")
+      )
+    })
+    .collect::<Vec<_>>()
+    .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::structures::Session;
+
+  #[test]
+  fn test_render_replaces_placeholders() {
+    let context = EventContext {
+      production: "Faust".to_string(),
+      person: "Anna".to_string(),
+      role: "Gretchen".to_string(),
+      ..Default::default()
+    };
+    assert_eq!(
+      render("{production}: {person} als {role}", &context),
+      "Faust: Anna als Gretchen"
+    );
+  }
+
+  #[test]
+  fn test_render_drops_empty_lines() {
+    let context = EventContext {
+      scenes: "1, 2".to_string(),
+      ..Default::default()
+    };
+    assert_eq!(
+      render(
+        &EventTemplates::for_language(Language::German).description,
+        &context
+      ),
+      "Szenen: 1, 2\n"
+    );
+  }
+
+  #[test]
+  fn test_special_entries_are_described_by_name() {
+    let context = EventContext {
+      special: "Hauptprobe".to_string(),
+      note: "Mit Kostüm".to_string(),
+      ..Default::default()
+    };
+    assert_eq!(
+      render(
+        &EventTemplates::for_language(Language::German).description,
+        &context
+      ),
+      "Hauptprobe\nAnmerkung: Mit Kostüm\n"
+    );
+  }
+
+  #[test]
+  fn test_render_keeps_unknown_placeholders() {
+    let context = EventContext::default();
+    assert_eq!(render("{unknown} {", &context), "{unknown} {");
+  }
+
+  #[test]
+  fn test_format_call_uses_room_of_session() {
+    let schedule_entry = ScheduleEntry::with_sessions(
+      chrono::NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(), None),
+      vec![
+        Session {
+          scenes: vec!["3".to_string(), "4".to_string()],
+          room: Some("Saal".to_string()),
+        },
+        Session {
+          scenes: vec!["7".to_string()],
+          room: None,
+        },
+      ],
+      None,
+    );
+    let chor = SceneEntry::new(
+      "Chor".to_string(),
+      "Chor".to_string(),
+      vec!["7".to_string()],
+      vec![false],
+    );
+    assert_eq!(
+      format_call(&schedule_entry, Some(&chor), "Theater"),
+      "01.05.2022 10:00, Theater: 7 (Chor)"
+    );
+    assert_eq!(
+      format_call(&schedule_entry, None, "Theater"),
+      "01.05.2022 10:00, Saal, Theater: 3, 4, 7"
+    );
+    assert_eq!(
+      format_sessions(&schedule_entry, "Theater"),
+      "Saal: 3, 4; Theater: 7"
+    );
+  }
+
+  #[test]
+  fn test_format_call_of_several_days() {
+    let date = |day| chrono::NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+    let schedule_entry = ScheduleEntry::new(
+      date(12),
+      (chrono::NaiveTime::MIN, None),
+      Scenes::Special("Gastspiel".to_string()),
+      Some("Basel".to_string()),
+      None,
+    )
+    .all_day(date(14));
+    assert_eq!(
+      format_call(&schedule_entry, None, "Theater"),
+      "12.04.2024–14.04.2024, Basel: Gastspiel"
+    );
+    assert_eq!(
+      schedule_entry.start_stop_date_time().1,
+      date(15).and_hms_opt(0, 0, 0)
+    );
+  }
+
+  #[test]
+  fn test_format_call_marks_inferred_stop_time() {
+    let time = |hour| chrono::NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+    let schedule_entry = ScheduleEntry::new(
+      chrono::NaiveDate::from_ymd_opt(2022, 5, 1).unwrap(),
+      (time(10), None),
+      Scenes::Normal(vec!["3".to_string()]),
+      None,
+      None,
+    );
+    assert_eq!(
+      format_call(&schedule_entry, None, "Theater"),
+      "01.05.2022 10:00, Theater: 3"
+    );
+    assert_eq!(
+      format_call(
+        &schedule_entry.with_inferred_stop_time(time(13)),
+        None,
+        "Theater"
+      ),
+      "01.05.2022 10:00–~13:00, Theater: 3"
+    );
+  }
+}